@@ -0,0 +1,66 @@
+//! Benchmarks of the hand-written lexer behind `parse_to_vec` and `parse`,
+//! against the pest grammar it replaced on those paths.
+//!
+//! Run with `cargo bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::fs;
+
+/// A large file in the shape of a monorepo's `.editorconfig`.
+fn large() -> String {
+	let mut contents = String::from("# top-most EditorConfig file\nroot = true\n");
+	for i in 0..1000 {
+		contents.push_str(&format!(
+			"\n[packages/{i}/**.{{js,ts}}]\n; package {i}\nindent_style = space\nindent_size = 2\n\
+			 end_of_line = lf\ntrim_trailing_whitespace = true\n"
+		));
+	}
+	contents
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+	vec![
+		(
+			"config.ini",
+			fs::read_to_string("tests/fixtures/config.ini").unwrap(),
+		),
+		("large", large()),
+	]
+}
+
+fn tokens(c: &mut Criterion) {
+	let mut group = c.benchmark_group("parse_to_vec");
+	for (name, contents) in inputs() {
+		assert_eq!(
+			one_ini::parse_to_vec(&contents).unwrap(),
+			one_ini::pest_to_vec(&contents).unwrap()
+		);
+		group.throughput(Throughput::Bytes(contents.len() as u64));
+		group.bench_with_input(BenchmarkId::new("pest", name), &contents, |b, contents| {
+			b.iter(|| one_ini::pest_to_vec(black_box(contents)).unwrap())
+		});
+		group.bench_with_input(BenchmarkId::new("lexer", name), &contents, |b, contents| {
+			b.iter(|| one_ini::parse_to_vec(black_box(contents)).unwrap())
+		});
+	}
+	group.finish();
+}
+
+fn ast(c: &mut Criterion) {
+	let mut group = c.benchmark_group("parse");
+	for (name, contents) in inputs() {
+		group.throughput(Throughput::Bytes(contents.len() as u64));
+		group.bench_with_input(BenchmarkId::new("lexer", name), &contents, |b, contents| {
+			b.iter(|| one_ini::parse(black_box(contents)).unwrap())
+		});
+		group.bench_with_input(
+			BenchmarkId::new("tokenize", name),
+			&contents,
+			|b, contents| b.iter(|| one_ini::tokenize(black_box(contents)).count()),
+		);
+	}
+	group.finish();
+}
+
+criterion_group!(benches, tokens, ast);
+criterion_main!(benches);
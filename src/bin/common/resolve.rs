@@ -0,0 +1,66 @@
+//! Resolution shared by `one-ini resolve` and `editorconfig`, which both
+//! print properties in the format of the `editorconfig` C CLI.
+
+use one_ini::{resolve_file_with, Version, DEFAULT_FILE_NAME};
+use std::io::{self, Write};
+
+/// The arguments `[-f NAME] [-b VERSION] FILEPATH...`.
+pub struct Request<'a> {
+	pub file_name: &'a str,
+	pub version: Version,
+	pub files: Vec<&'a str>,
+}
+
+impl<'a> Request<'a> {
+	/// Reads the arguments, returning a usage error message if they are
+	/// invalid.
+	pub fn from_args(args: &'a [String]) -> Result<Self, String> {
+		let mut request = Request {
+			file_name: DEFAULT_FILE_NAME,
+			version: Version::CURRENT,
+			files: vec![],
+		};
+		let mut args = args.iter();
+		while let Some(arg) = args.next() {
+			match arg.as_str() {
+				"-f" => {
+					request.file_name = args.next().ok_or("missing value for `-f`")?;
+				}
+				"-b" => {
+					let value = args.next().ok_or("missing value for `-b`")?;
+					request.version = value.parse().map_err(|e| format!("{}", e))?;
+					if request.version > Version::CURRENT {
+						return Err(format!(
+							"version {} is newer than {}",
+							request.version,
+							Version::CURRENT
+						));
+					}
+				}
+				arg if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+				arg => request.files.push(arg),
+			}
+		}
+		if request.files.is_empty() {
+			return Err(String::from("missing FILEPATH"));
+		}
+		Ok(request)
+	}
+
+	/// Writes the `key=value` properties of each file, under a `[path]`
+	/// header when there are several files, returning an error message if a
+	/// config file cannot be read or parsed.
+	pub fn write(&self, out: &mut impl Write) -> Result<(), String> {
+		let headers = self.files.len() > 1;
+		for file in &self.files {
+			let properties =
+				resolve_file_with(file, self.file_name, self.version).map_err(|e| e.to_string())?;
+			let written = match headers {
+				true => write!(out, "[{}]\n{}", file, properties),
+				false => write!(out, "{}", properties),
+			};
+			written.map_err(|e: io::Error| e.to_string())?;
+		}
+		Ok(())
+	}
+}
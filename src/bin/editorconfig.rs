@@ -0,0 +1,91 @@
+//! # editorconfig
+//!
+//! A drop-in replacement for the `editorconfig` CLI of the
+//! [EditorConfig C core](https://github.com/editorconfig/editorconfig-core-c),
+//! built on the one-ini resolver.
+//!
+//! Unlike the C core, relative file paths are accepted, and made absolute
+//! against the current directory.
+
+#[path = "common/resolve.rs"]
+mod resolve;
+
+use one_ini::Version;
+use resolve::Request;
+use std::process::ExitCode;
+use std::{env, io};
+
+const USAGE: &str = "\
+Usage: editorconfig [OPTIONS] FILEPATH1 [FILEPATH2 FILEPATH3 ...]
+
+FILEPATH can be a hyphen (-) if you want path(s) to be read from stdin.
+
+-f                 Specify conf filename other than \".editorconfig\".
+-b                 Specify version (used by devs to test compatibility).
+-h OR --help       Print this help message.
+-v OR --version    Display version information.
+";
+
+fn main() -> ExitCode {
+	let args: Vec<String> = env::args().skip(1).collect();
+	if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+		print!("{}", USAGE);
+		return ExitCode::SUCCESS;
+	}
+	if args.iter().any(|arg| arg == "-v" || arg == "--version") {
+		println!(
+			"EditorConfig one-ini Version {} (core version {})",
+			one_ini::version(),
+			Version::CURRENT
+		);
+		return ExitCode::SUCCESS;
+	}
+	let args = match read_stdin_paths(args) {
+		Ok(args) => args,
+		Err(e) => {
+			eprintln!("editorconfig: {}", e);
+			return ExitCode::FAILURE;
+		}
+	};
+	let request = match Request::from_args(&args) {
+		Ok(request) => request,
+		Err(message) => {
+			eprintln!("editorconfig: {}\n\n{}", message, USAGE);
+			return ExitCode::FAILURE;
+		}
+	};
+	match request.write(&mut io::stdout().lock()) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(message) => {
+			eprintln!("editorconfig: {}", message);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+/// Replaces a `-` file path with the paths read from standard input, one
+/// per line.
+fn read_stdin_paths(args: Vec<String>) -> io::Result<Vec<String>> {
+	if !args.iter().any(|arg| arg == "-") {
+		return Ok(args);
+	}
+	let stdin = io::read_to_string(io::stdin())?;
+	let mut expanded = vec![];
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-" => expanded.extend(
+				stdin
+					.lines()
+					.filter(|line| !line.is_empty())
+					.map(String::from),
+			),
+			"-f" | "-b" => {
+				expanded.push(arg);
+				expanded.extend(args.next());
+			}
+			_ => expanded.push(arg),
+		}
+	}
+	Ok(expanded)
+}
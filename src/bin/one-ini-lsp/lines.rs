@@ -0,0 +1,44 @@
+//! Conversions between byte offsets and LSP positions, whose `line` is
+//! zero-based and whose `character` counts UTF-16 code units.
+
+use one_ini::{LineIndex, OffsetEncoding};
+use serde_json::{json, Value};
+
+/// The line starts of a document, where `\n`, `\r\n` and `\r` all end a
+/// line.
+pub struct Lines<'a> {
+	index: LineIndex<'a>,
+	len: usize,
+}
+
+impl<'a> Lines<'a> {
+	pub fn new(text: &'a str) -> Self {
+		Lines {
+			index: LineIndex::new(text),
+			len: text.len(),
+		}
+	}
+
+	/// The zero-based line and UTF-16 character of a byte offset.
+	pub fn position(&self, offset: usize) -> (usize, usize) {
+		self.index.line_col(offset, OffsetEncoding::Utf16)
+	}
+
+	/// The byte offset of a zero-based line and UTF-16 character, clamped to
+	/// the end of the line, or to the end of the document past its last line.
+	pub fn offset(&self, line: usize, character: usize) -> usize {
+		self.index
+			.offset(line, character, OffsetEncoding::Utf16)
+			.unwrap_or(self.len)
+	}
+
+	/// The LSP `Range` between two byte offsets.
+	pub fn range(&self, start: usize, end: usize) -> Value {
+		let (start_line, start_character) = self.position(start);
+		let (end_line, end_character) = self.position(end);
+		json!({
+			"start": { "line": start_line, "character": start_character },
+			"end": { "line": end_line, "character": end_character },
+		})
+	}
+}
@@ -0,0 +1,454 @@
+//! # one-ini-lsp
+//!
+//! A [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! server for `.editorconfig` files, speaking JSON-RPC over stdio.
+//!
+//! Documents are parsed with [`parse_recovering`], so every feature keeps
+//! working while a file has invalid lines.
+
+mod lines;
+mod rpc;
+
+use lines::Lines;
+use one_ini::{
+	format, lint, parse_recovering, FormatOptions, Item, OneINIAST, Property, Severity, TokenTypes,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufReader};
+use std::process::ExitCode;
+
+/// The semantic token types, indexed by [TokenTypes] so that each token's
+/// type is its `TokenTypes` value.
+const TOKEN_LEGEND: [&str; 5] = ["property", "string", "namespace", "comment", "comment"];
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn main() -> ExitCode {
+	let mut reader = BufReader::new(io::stdin().lock());
+	let mut writer = io::stdout().lock();
+	let mut server = Server::default();
+	loop {
+		let outgoing = match rpc::read_message(&mut reader) {
+			Ok(Some(message)) => {
+				let method = message["method"].as_str().unwrap_or_default();
+				if method == "exit" {
+					return match server.shut_down {
+						true => ExitCode::SUCCESS,
+						false => ExitCode::FAILURE,
+					};
+				}
+				match message.get("id") {
+					Some(id) => vec![server.request(id, method, &message["params"])],
+					None => server.notification(method, &message["params"]),
+				}
+			}
+			Ok(None) => return ExitCode::FAILURE,
+			// The id of a malformed message is unknown, so it is answered
+			// with a null one, and the server keeps reading.
+			Err(e) if e.kind() == io::ErrorKind::InvalidData => vec![json!({
+				"jsonrpc": "2.0",
+				"id": null,
+				"error": { "code": PARSE_ERROR, "message": e.to_string() },
+			})],
+			Err(e) => {
+				eprintln!("one-ini-lsp: {}", e);
+				return ExitCode::FAILURE;
+			}
+		};
+		for message in outgoing {
+			if let Err(e) = rpc::write_message(&mut writer, &message) {
+				eprintln!("one-ini-lsp: {}", e);
+				return ExitCode::FAILURE;
+			}
+		}
+	}
+}
+
+#[derive(Default)]
+struct Server {
+	/// The text of each open document, by URI.
+	documents: HashMap<String, String>,
+	format_options: FormatOptions,
+	shut_down: bool,
+}
+
+impl Server {
+	fn request(&mut self, id: &Value, method: &str, params: &Value) -> Value {
+		let result = match method {
+			"initialize" => Ok(self.initialize(params)),
+			"shutdown" => {
+				self.shut_down = true;
+				Ok(Value::Null)
+			}
+			"textDocument/completion" => self.with_position(params, completion),
+			"textDocument/hover" => self.with_position(params, hover),
+			"textDocument/documentSymbol" => self.with_document(params, document_symbols),
+			"textDocument/formatting" => {
+				let options = self.format_options.clone();
+				self.with_document(params, |text| formatting(text, options))
+			}
+			"textDocument/semanticTokens/full" => self.with_document(params, semantic_tokens),
+			_ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+		};
+		match result {
+			Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+			Err((code, message)) => json!({
+				"jsonrpc": "2.0",
+				"id": id,
+				"error": { "code": code, "message": message },
+			}),
+		}
+	}
+
+	/// Handles a notification, returning the notifications to send back.
+	fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+		let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+		let text = match method {
+			"textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+			// Documents are synced in full, so the last change is the whole text.
+			"textDocument/didChange" => params["contentChanges"]
+				.as_array()
+				.and_then(|changes| changes.last())
+				.and_then(|change| change["text"].as_str()),
+			"textDocument/didClose" => {
+				self.documents.remove(uri);
+				return vec![publish_diagnostics(uri, vec![])];
+			}
+			_ => None,
+		};
+		match text {
+			Some(text) => {
+				self.documents.insert(String::from(uri), String::from(text));
+				vec![publish_diagnostics(uri, diagnostics(text))]
+			}
+			None => vec![],
+		}
+	}
+
+	fn initialize(&mut self, params: &Value) -> Value {
+		let options = &params["initializationOptions"]["format"];
+		if let Ok(options) = serde_json::from_value(options.clone()) {
+			self.format_options = options;
+		}
+		json!({
+			"capabilities": {
+				"textDocumentSync": 1,
+				"completionProvider": { "triggerCharacters": ["="] },
+				"hoverProvider": true,
+				"documentSymbolProvider": true,
+				"documentFormattingProvider": true,
+				"semanticTokensProvider": {
+					"legend": { "tokenTypes": TOKEN_LEGEND, "tokenModifiers": [] },
+					"full": true,
+				},
+			},
+			"serverInfo": { "name": "one-ini-lsp", "version": one_ini::version() },
+		})
+	}
+
+	fn with_document(
+		&self,
+		params: &Value,
+		handler: impl FnOnce(&str) -> Value,
+	) -> Result<Value, (i64, String)> {
+		let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+		match self.documents.get(uri) {
+			Some(text) => Ok(handler(text)),
+			None => Err((INVALID_PARAMS, format!("unknown document `{}`", uri))),
+		}
+	}
+
+	fn with_position(
+		&self,
+		params: &Value,
+		handler: impl FnOnce(&str, usize) -> Value,
+	) -> Result<Value, (i64, String)> {
+		let line = params["position"]["line"].as_u64().unwrap_or_default() as usize;
+		let character = params["position"]["character"].as_u64().unwrap_or_default() as usize;
+		self.with_document(params, |text| {
+			let offset = Lines::new(text).offset(line, character);
+			handler(text, offset)
+		})
+	}
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+	json!({
+		"jsonrpc": "2.0",
+		"method": "textDocument/publishDiagnostics",
+		"params": { "uri": uri, "diagnostics": diagnostics },
+	})
+}
+
+/// Lint diagnostics, which include a diagnostic for every line the parser
+/// could not parse.
+fn diagnostics(text: &str) -> Vec<Value> {
+	let lines = Lines::new(text);
+	let (ast, _) = parse_recovering(text);
+	lint(&ast)
+		.into_iter()
+		.map(|diagnostic| {
+			let range = match diagnostic.span {
+				Some(span) => lines.range(span.start.offset, span.end.offset),
+				None => lines.range(0, 0),
+			};
+			let severity = match diagnostic.severity {
+				Severity::Error => 1,
+				Severity::Warning => 2,
+				Severity::Info => 3,
+			};
+			json!({
+				"range": range,
+				"severity": severity,
+				"code": diagnostic.code.as_str(),
+				"source": "one-ini",
+				"message": diagnostic.message,
+			})
+		})
+		.collect()
+}
+
+/// Suggests property names before the `=` of a line, and the keywords of the
+/// property after it.
+fn completion(text: &str, offset: usize) -> Value {
+	let line_start = text[..offset].rfind(['\r', '\n']).map_or(0, |i| i + 1);
+	let before = text[line_start..offset].trim_start_matches([' ', '\t']);
+	if before.starts_with(['[', '#', ';']) {
+		return json!([]);
+	}
+	let items: Vec<Value> = match before.split_once('=') {
+		Some((key, _)) => {
+			let key = key.trim_end_matches([' ', '\t']);
+			// `root` is the only property that cannot be unset.
+			let unset: &[&str] = match key.eq_ignore_ascii_case("root") {
+				true => &[],
+				false => &["unset"],
+			};
+			Property::keywords(key)
+				.iter()
+				.chain(unset)
+				.map(|value| json!({ "label": value, "kind": 12 }))
+				.collect()
+		}
+		None => {
+			let (ast, _) = parse_recovering(text);
+			let in_prelude = !ast.body.iter().any(|item| match item {
+				Item::Section(section) => {
+					section.span.is_some_and(|span| span.start.offset < offset)
+				}
+				_ => false,
+			});
+			Property::KEYS
+				.iter()
+				.filter(|&&key| (key == "root") == in_prelude)
+				.map(|key| {
+					json!({
+						"label": key,
+						"kind": 10,
+						"documentation": { "kind": "markdown", "value": describe(key) },
+					})
+				})
+				.collect()
+		}
+	};
+	json!(items)
+}
+
+/// Describes the property under the cursor.
+fn hover(text: &str, offset: usize) -> Value {
+	let (ast, _) = parse_recovering(text);
+	let pair = pairs(&ast).find(|pair| {
+		pair.key_span
+			.is_some_and(|span| span.start.offset <= offset && offset <= span.end.offset)
+	});
+	let Some(pair) = pair else {
+		return Value::Null;
+	};
+	let key = pair.key.to_lowercase();
+	if !Property::KEYS.contains(&key.as_str()) {
+		return Value::Null;
+	}
+	let span = pair.key_span.unwrap();
+	json!({
+		"contents": {
+			"kind": "markdown",
+			"value": format!("**{}**\n\n{}", key, describe(&key)),
+		},
+		"range": Lines::new(text).range(span.start.offset, span.end.offset),
+	})
+}
+
+fn pairs(ast: &OneINIAST) -> impl Iterator<Item = &one_ini::Pair> {
+	ast.body
+		.iter()
+		.flat_map(|item| match item {
+			Item::Section(section) => section.body.iter().collect(),
+			item => vec![item],
+		})
+		.filter_map(|item| match item {
+			Item::Pair(pair) => Some(pair),
+			_ => None,
+		})
+}
+
+/// Sections, with their pairs as children, and the pairs of the prelude.
+fn document_symbols(text: &str) -> Value {
+	let lines = Lines::new(text);
+	let (ast, _) = parse_recovering(text);
+	let pair_symbol = |pair: &one_ini::Pair| {
+		let (span, key_span) = (pair.span.unwrap(), pair.key_span.unwrap());
+		json!({
+			"name": pair.key,
+			"detail": pair.value,
+			"kind": 7,
+			"range": lines.range(span.start.offset, span.end.offset),
+			"selectionRange": lines.range(key_span.start.offset, key_span.end.offset),
+		})
+	};
+	let symbols: Vec<Value> = ast
+		.body
+		.iter()
+		.filter_map(|item| match item {
+			Item::Pair(pair) => Some(pair_symbol(pair)),
+			Item::Section(section) => {
+				let (span, name_span) = (section.span.unwrap(), section.name_span.unwrap());
+				let children: Vec<Value> = section
+					.body
+					.iter()
+					.filter_map(|item| match item {
+						Item::Pair(pair) => Some(pair_symbol(pair)),
+						_ => None,
+					})
+					.collect();
+				Some(json!({
+					"name": section.name,
+					"kind": 3,
+					"range": lines.range(span.start.offset, span.end.offset),
+					"selectionRange": lines.range(name_span.start.offset, name_span.end.offset),
+					"children": children,
+				}))
+			}
+			_ => None,
+		})
+		.collect();
+	json!(symbols)
+}
+
+/// Replaces the whole document with its formatted text, if that differs.
+fn formatting(text: &str, options: FormatOptions) -> Value {
+	let formatted = format(text, options);
+	if formatted == text {
+		return json!([]);
+	}
+	json!([{ "range": Lines::new(text).range(0, text.len()), "newText": formatted }])
+}
+
+/// Encodes the tokens of the document as LSP semantic tokens, relative to
+/// the previous token.
+fn semantic_tokens(text: &str) -> Value {
+	let lines = Lines::new(text);
+	let (ast, _) = parse_recovering(text);
+	let mut tokens = vec![];
+	collect_tokens(&ast.body, &mut tokens);
+	let mut data = vec![];
+	let (mut previous_line, mut previous_character) = (0, 0);
+	for (start, end, kind) in tokens {
+		if start == end {
+			continue;
+		}
+		let (line, character) = lines.position(start);
+		let length: usize = text[start..end].chars().map(char::len_utf16).sum();
+		let delta_character = match line == previous_line {
+			true => character - previous_character,
+			false => character,
+		};
+		data.extend([
+			line - previous_line,
+			delta_character,
+			length,
+			kind as usize,
+			0,
+		]);
+		(previous_line, previous_character) = (line, character);
+	}
+	json!({ "data": data })
+}
+
+/// Collects the byte range and type of each token, in document order.
+fn collect_tokens(items: &[Item], tokens: &mut Vec<(usize, usize, TokenTypes)>) {
+	for item in items {
+		match item {
+			Item::Section(section) => {
+				if let Some(span) = section.name_span {
+					tokens.push((span.start.offset, span.end.offset, TokenTypes::Section));
+				}
+				collect_tokens(&section.body, tokens);
+			}
+			Item::Pair(pair) => {
+				if let (Some(key), Some(value)) = (pair.key_span, pair.value_span) {
+					tokens.push((key.start.offset, key.end.offset, TokenTypes::Key));
+					tokens.push((value.start.offset, value.end.offset, TokenTypes::Value));
+				}
+			}
+			Item::Comment(comment) => {
+				if let Some(span) = comment.span {
+					let indicator_end = span.start.offset + comment.indicator.len_utf8();
+					tokens.push((
+						span.start.offset,
+						indicator_end,
+						TokenTypes::CommentIndicator,
+					));
+					tokens.push((indicator_end, span.end.offset, TokenTypes::CommentValue));
+				}
+			}
+			Item::Error(_) => (),
+		}
+	}
+}
+
+/// Describes a supported property, following the specification.
+fn describe(key: &str) -> &'static str {
+	match key {
+		"indent_style" => "Set to `tab` or `space` to use hard tabs or soft tabs respectively.",
+		"indent_size" => {
+			"A whole number defining the number of columns used for each indentation level and \
+			 the width of soft tabs (when supported). When set to `tab`, the value of \
+			 `tab_width` (if specified) will be used."
+		}
+		"tab_width" => {
+			"A whole number defining the number of columns used to represent a tab character. \
+			 This defaults to the value of `indent_size` and should only be specified if it \
+			 differs."
+		}
+		"end_of_line" => "Set to `lf`, `cr`, or `crlf` to control how line breaks are represented.",
+		"charset" => {
+			"Set to `latin1`, `utf-8`, `utf-8-bom`, `utf-16be` or `utf-16le` to control the \
+			 character set."
+		}
+		"spelling_language" => {
+			"Sets the natural language that should be used for spell checking, such as `en-US`. \
+			 Only one language can be specified."
+		}
+		"trim_trailing_whitespace" => {
+			"Set to `true` to remove all whitespace characters preceding newline characters in \
+			 the file and `false` to ensure it doesn't."
+		}
+		"insert_final_newline" => {
+			"Set to `true` to ensure file ends with a newline when saving and `false` to ensure \
+			 it doesn't."
+		}
+		"max_line_length" => {
+			"Forces hard line wrapping after the amount of characters specified. `off` to turn \
+			 off this feature (use the editor settings)."
+		}
+		"root" => {
+			"Special property that should be specified at the top of the file outside of any \
+			 sections. Set to `true` to stop the search for `.editorconfig` files on the current \
+			 file."
+		}
+		_ => "",
+	}
+}
@@ -0,0 +1,43 @@
+//! JSON-RPC messages framed with `Content-Length` headers, as the Language
+//! Server Protocol sends them over stdio.
+
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Reads the next message, or `None` at the end of input. Malformed headers
+/// and bodies are `InvalidData` errors, after which reading can go on with
+/// the next message.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+	let mut length = None;
+	loop {
+		let mut header = String::new();
+		if reader.read_line(&mut header)? == 0 {
+			return Ok(None);
+		}
+		let header = header.trim_end_matches(['\r', '\n']);
+		if header.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = header.split_once(':') {
+			if name.eq_ignore_ascii_case("Content-Length") {
+				length = value.trim().parse::<usize>().ok();
+			}
+		}
+	}
+	let length = length.ok_or_else(|| invalid_data("missing Content-Length header"))?;
+	let mut body = vec![0; length];
+	reader.read_exact(&mut body)?;
+	serde_json::from_slice(&body)
+		.map(Some)
+		.map_err(|e| invalid_data(&e.to_string()))
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+	let body = message.to_string();
+	write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+	writer.flush()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,360 @@
+//! # one-ini
+//!
+//! Command-line access to the parser, formatter, linter and resolver, for
+//! shell scripts and tools that cannot link the library.
+
+#[path = "common/resolve.rs"]
+mod resolve;
+
+use one_ini::{
+	check, check_tree, fix, format, lint, parse, parse_recovering, parse_to_vec,
+	parse_to_vec_with_trivia, resolve_file_with, CommentIndicator, EndOfLine, FormatOptions,
+	Severity, TokenTypes, Version, Violation, DEFAULT_FILE_NAME,
+};
+use resolve::Request;
+use std::path::Path;
+use std::process::ExitCode;
+use std::{env, fmt, fs, io};
+
+const USAGE: &str = "\
+Usage: one-ini <COMMAND> [ARGS]
+
+Commands:
+  parse [FILE]                             Print the AST as JSON
+  tokens [--trivia] [FILE]                 Print the token stream, one token per line
+  fmt [OPTIONS] [FILE...]                  Format files, printing the result
+  lint [FILE...]                           Report problems in files
+  resolve [-f NAME] [-b VERSION] FILE...   Print the properties that apply to files
+  check [-f NAME] [--fix] [PATH...]        Report where files do not follow their properties
+
+Reads standard input when FILE is `-` or missing. `check` checks every file
+under each directory PATH, and the current directory when PATH is missing.
+
+Options for tokens:
+  --trivia       Also print brackets, `=`, whitespace, line breaks and invalid lines
+
+Options for fmt:
+  --check                    Print the files that are not formatted, and fail if any
+  --write                    Rewrite the files that are not formatted
+  --spaces-around-equals     Write `key = value` rather than `key=value`
+  --lowercase-keys           Lowercase every key
+  --blank-lines <N>          Put N blank lines between sections [default: 1]
+  --comment-indicator <C>    Start every comment with C, either `#` or `;`
+  --end-of-line <EOL>        End lines with `lf`, `crlf` or `cr` [default: lf]
+  --align-equals             Line up the `=` of the pairs in each section
+
+Options for resolve:
+  -f <NAME>      Read config files named NAME [default: .editorconfig]
+  -b <VERSION>   Resolve as the given EditorConfig core version would
+
+Options for check:
+  -f <NAME>      Read config files named NAME [default: .editorconfig]
+  --fix          Rewrite the files, then report what could not be fixed
+";
+
+fn main() -> ExitCode {
+	let args: Vec<String> = env::args().skip(1).collect();
+	match run(&args) {
+		Ok(code) => code,
+		Err(Error::Usage(message)) => {
+			eprintln!("one-ini: {}\n\n{}", message, USAGE);
+			ExitCode::from(2)
+		}
+		Err(Error::Failed(message)) => {
+			eprintln!("one-ini: {}", message);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+enum Error {
+	/// The arguments are invalid.
+	Usage(String),
+	/// The command could not be completed.
+	Failed(String),
+}
+
+fn run(args: &[String]) -> Result<ExitCode, Error> {
+	let (command, args) = match args.split_first() {
+		Some((command, args)) => (command.as_str(), args),
+		None => return Err(Error::Usage(String::from("missing command"))),
+	};
+	match command {
+		"parse" => parse_command(args),
+		"tokens" => tokens_command(args),
+		"fmt" => fmt_command(args),
+		"lint" => lint_command(args),
+		"resolve" => resolve_command(args),
+		"check" => check_command(args),
+		"-h" | "--help" | "help" => {
+			print!("{}", USAGE);
+			Ok(ExitCode::SUCCESS)
+		}
+		"-V" | "--version" => {
+			println!("one-ini {}", one_ini::version());
+			Ok(ExitCode::SUCCESS)
+		}
+		_ => Err(Error::Usage(format!("unknown command `{}`", command))),
+	}
+}
+
+fn parse_command(args: &[String]) -> Result<ExitCode, Error> {
+	let input = Input::read(single_file(args)?)?;
+	let ast =
+		parse(&input.contents).map_err(|e| Error::Failed(format!("{}: {}", input.name, e)))?;
+	// Serializing the AST to a string cannot fail.
+	println!("{}", serde_json::to_string_pretty(&ast).unwrap());
+	Ok(ExitCode::SUCCESS)
+}
+
+fn tokens_command(args: &[String]) -> Result<ExitCode, Error> {
+	let (trivia, args) = match args.split_first() {
+		Some((flag, args)) if flag == "--trivia" => (true, args),
+		_ => (false, args),
+	};
+	let input = Input::read(single_file(args)?)?;
+	let tokens = match trivia {
+		true => parse_to_vec_with_trivia(&input.contents),
+		false => parse_to_vec(&input.contents)
+			.map_err(|e| Error::Failed(format!("{}: {}", input.name, e)))?,
+	};
+	for token in tokens.chunks(3) {
+		let (start, end) = (token[1] as usize, token[2] as usize);
+		let kind = match TokenTypes::try_from(token[0]) {
+			Ok(kind) => format!("{:?}", kind),
+			Err(kind) => kind.to_string(),
+		};
+		println!(
+			"{:<16} {}..{} {:?}",
+			kind,
+			start,
+			end,
+			&input.contents[start..end]
+		);
+	}
+	Ok(ExitCode::SUCCESS)
+}
+
+fn fmt_command(args: &[String]) -> Result<ExitCode, Error> {
+	let mut options = FormatOptions::default();
+	let (mut check, mut write) = (false, false);
+	let mut files = vec![];
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--check" => check = true,
+			"--write" => write = true,
+			"--spaces-around-equals" => options.spaces_around_equals = true,
+			"--lowercase-keys" => options.lowercase_keys = true,
+			"--align-equals" => options.align_equals = true,
+			"--blank-lines" => {
+				let value = option_value(arg, args.next())?;
+				options.blank_lines_between_sections = value.parse().map_err(|_| {
+					Error::Usage(format!("invalid number of blank lines `{}`", value))
+				})?;
+			}
+			"--comment-indicator" => {
+				options.comment_indicator = match option_value(arg, args.next())? {
+					"#" => Some(CommentIndicator::Hash),
+					";" => Some(CommentIndicator::Semicolon),
+					value => {
+						return Err(Error::Usage(format!(
+							"invalid comment indicator `{}`, expected `#` or `;`",
+							value
+						)))
+					}
+				};
+			}
+			"--end-of-line" => {
+				options.end_of_line = match option_value(arg, args.next())? {
+					"lf" => EndOfLine::Lf,
+					"crlf" => EndOfLine::Crlf,
+					"cr" => EndOfLine::Cr,
+					value => {
+						return Err(Error::Usage(format!(
+							"invalid line ending `{}`, expected `lf`, `crlf` or `cr`",
+							value
+						)))
+					}
+				};
+			}
+			_ => files.push(file_arg(arg)?),
+		}
+	}
+	if check && write {
+		return Err(Error::Usage(String::from(
+			"`--check` and `--write` cannot be used together",
+		)));
+	}
+	if files.is_empty() {
+		files.push("-");
+	}
+	let mut unformatted = false;
+	for file in files {
+		let input = Input::read(file)?;
+		let formatted = format(&input.contents, options.clone());
+		if check {
+			if formatted != input.contents {
+				println!("{}", input.name);
+				unformatted = true;
+			}
+		} else if write && file != "-" {
+			if formatted != input.contents {
+				fs::write(file, formatted)
+					.map_err(|e| Error::Failed(format!("{}: {}", file, e)))?;
+			}
+		} else {
+			print!("{}", formatted);
+		}
+	}
+	match unformatted {
+		true => Ok(ExitCode::FAILURE),
+		false => Ok(ExitCode::SUCCESS),
+	}
+}
+
+fn lint_command(args: &[String]) -> Result<ExitCode, Error> {
+	let mut files = args
+		.iter()
+		.map(|arg| file_arg(arg))
+		.collect::<Result<Vec<_>, _>>()?;
+	if files.is_empty() {
+		files.push("-");
+	}
+	let mut failed = false;
+	for file in files {
+		let input = Input::read(file)?;
+		let (ast, _) = parse_recovering(&input.contents);
+		for diagnostic in lint(&ast) {
+			failed |= diagnostic.severity == Severity::Error;
+			match diagnostic.span {
+				Some(span) => println!(
+					"{}:{}:{}: {}[{}]: {}",
+					input.name,
+					span.start.line,
+					span.start.column,
+					diagnostic.severity,
+					diagnostic.code,
+					diagnostic.message
+				),
+				None => println!(
+					"{}: {}[{}]: {}",
+					input.name, diagnostic.severity, diagnostic.code, diagnostic.message
+				),
+			}
+		}
+	}
+	match failed {
+		true => Ok(ExitCode::FAILURE),
+		false => Ok(ExitCode::SUCCESS),
+	}
+}
+
+/// Prints properties in the format of the `editorconfig` C CLI.
+fn resolve_command(args: &[String]) -> Result<ExitCode, Error> {
+	let request = Request::from_args(args).map_err(Error::Usage)?;
+	request
+		.write(&mut io::stdout().lock())
+		.map_err(Error::Failed)?;
+	Ok(ExitCode::SUCCESS)
+}
+
+/// Prints violations as `path:line:col: code: message`, and fails if there
+/// are any.
+fn check_command(args: &[String]) -> Result<ExitCode, Error> {
+	let mut file_name = DEFAULT_FILE_NAME;
+	let mut fix_files = false;
+	let mut paths = vec![];
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-f" => file_name = option_value(arg, args.next())?,
+			"--fix" => fix_files = true,
+			"-" => return Err(Error::Usage(String::from("cannot check standard input"))),
+			_ => paths.push(file_arg(arg)?),
+		}
+	}
+	if paths.is_empty() {
+		paths.push(".");
+	}
+	let mut failed = false;
+	for path in paths {
+		let results = check_tree(path, file_name).map_err(|e| Error::Failed(e.to_string()))?;
+		for (path, mut violations) in results {
+			if fix_files {
+				violations = fix_file(&path, file_name)?;
+			}
+			for violation in violations {
+				failed = true;
+				println!(
+					"{}:{}:{}: {}: {}",
+					path.display(),
+					violation.position.line,
+					violation.position.column,
+					violation.code,
+					violation.message
+				);
+			}
+		}
+	}
+	match failed {
+		true => Ok(ExitCode::FAILURE),
+		false => Ok(ExitCode::SUCCESS),
+	}
+}
+
+/// Rewrites a file to follow its properties, returning the violations that
+/// are left.
+fn fix_file(path: &Path, file_name: &str) -> Result<Vec<Violation>, Error> {
+	let failed = |e: &dyn fmt::Display| Error::Failed(format!("{}: {}", path.display(), e));
+	let properties =
+		resolve_file_with(path, file_name, Version::CURRENT).map_err(|e| failed(&e))?;
+	let contents = fs::read(path).map_err(|e| failed(&e))?;
+	let fixed = fix(&contents, &properties);
+	if fixed != contents {
+		fs::write(path, &fixed).map_err(|e| failed(&e))?;
+	}
+	Ok(check(&fixed, &properties))
+}
+
+/// The contents of a file, or of standard input.
+struct Input {
+	name: String,
+	contents: String,
+}
+
+impl Input {
+	fn read(file: &str) -> Result<Self, Error> {
+		let contents = match file {
+			"-" => io::read_to_string(io::stdin()),
+			file => fs::read_to_string(file),
+		}
+		.map_err(|e| Error::Failed(format!("{}: {}", file, e)))?;
+		let name = match file {
+			"-" => String::from("<stdin>"),
+			file => String::from(file),
+		};
+		Ok(Input { name, contents })
+	}
+}
+
+fn single_file(args: &[String]) -> Result<&str, Error> {
+	match args {
+		[] => Ok("-"),
+		[file] => file_arg(file),
+		_ => Err(Error::Usage(String::from("expected at most one FILE"))),
+	}
+}
+
+fn file_arg(arg: &str) -> Result<&str, Error> {
+	match arg.starts_with('-') && arg != "-" {
+		true => Err(Error::Usage(format!("unknown option `{}`", arg))),
+		false => Ok(arg),
+	}
+}
+
+fn option_value<'a>(option: &str, value: Option<&'a String>) -> Result<&'a str, Error> {
+	value
+		.map(String::as_str)
+		.ok_or_else(|| Error::Usage(format!("missing value for `{}`", option)))
+}
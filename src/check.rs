@@ -0,0 +1,529 @@
+//! # Checking
+//!
+//! Reports the places where a file does not follow the
+//! [properties](crate::Properties) that apply to it, in the way
+//! [eclint](https://github.com/editorconfig-checker/editorconfig-checker)-style
+//! tools do. Properties are resolved with this crate's own glob matching, so
+//! files are checked against exactly the properties editors using the library
+//! apply.
+
+use crate::{
+	resolve_file_with, Charset, EndOfLine, IndentSize, IndentStyle, LookupError, MaxLineLength,
+	Position, Properties, Version,
+};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// What a [Violation] reports. Each code has a stable, kebab-case name, the
+/// same as the name of the property it checks where there is one.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ViolationCode {
+	/// A line ending other than the `end_of_line`.
+	EndOfLine,
+	/// Indentation with tabs where `indent_style` is `space`, or with spaces
+	/// where it is `tab`.
+	IndentStyle,
+	/// Space indentation that is not a multiple of the `indent_size`.
+	IndentSize,
+	/// Whitespace at the end of a line, where `trim_trailing_whitespace` is
+	/// `true`.
+	TrailingWhitespace,
+	/// A missing final newline where `insert_final_newline` is `true`, or one
+	/// that is present where it is `false`.
+	FinalNewline,
+	/// A line longer than the `max_line_length`.
+	MaxLineLength,
+	/// A byte order mark or encoding that does not match the `charset`.
+	Charset,
+}
+
+impl ViolationCode {
+	/// The stable name of the code, such as `trailing-whitespace`.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ViolationCode::EndOfLine => "end-of-line",
+			ViolationCode::IndentStyle => "indent-style",
+			ViolationCode::IndentSize => "indent-size",
+			ViolationCode::TrailingWhitespace => "trailing-whitespace",
+			ViolationCode::FinalNewline => "final-newline",
+			ViolationCode::MaxLineLength => "max-line-length",
+			ViolationCode::Charset => "charset",
+		}
+	}
+}
+
+impl fmt::Display for ViolationCode {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(self.as_str())
+	}
+}
+
+/// A place where a file does not follow its properties, found by [check].
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{Properties, ViolationCode};
+///
+/// let mut properties = Properties::new();
+/// properties.insert("trim_trailing_whitespace", "true");
+///
+/// let violations = one_ini::check(b"fn main() {}  \n", &properties);
+///
+/// assert_eq!(violations[0].code, ViolationCode::TrailingWhitespace);
+/// assert_eq!(
+///     violations[0].to_string(),
+///     "trailing-whitespace: trailing whitespace at line 1, column 13",
+/// );
+/// ```
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+	pub code: ViolationCode,
+	/// A human-readable description of the problem, without its location.
+	pub message: String,
+	/// Where the problem starts. Columns are counted in characters, and start
+	/// after a byte order mark.
+	pub position: Position,
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			formatter,
+			"{}: {} at line {}, column {}",
+			self.code, self.message, self.position.line, self.position.column
+		)
+	}
+}
+
+/// Checks the contents of a file against the properties that apply to it,
+/// returning the violations in the order they appear in.
+///
+/// Only the properties that are set are checked. Files whose `charset` is
+/// UTF-16 are only checked for their byte order mark. Space indentation
+/// that is one more than a multiple of the `indent_size` is allowed before
+/// a `*`, for the continuation lines of block comments. Fewer spaces than a
+/// tab stop after tab indentation align rather than indent, as [fix](crate::fix)
+/// leaves them, where the tab stop is the `indent_size` or the `tab_width`.
+/// Tabs count up to the next multiple of the `tab_width` towards the `max_line_length`, or as one
+/// column when it is not set.
+///
+/// # Example
+///
+/// ```
+/// let mut properties = one_ini::Properties::new();
+/// properties.insert("end_of_line", "lf");
+/// properties.insert("insert_final_newline", "true");
+///
+/// let violations = one_ini::check(b"a\r\nb", &properties);
+/// let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+///
+/// assert_eq!(
+///     messages,
+///     [
+///         "end-of-line: line ends with `crlf`, expected `lf` at line 1, column 2",
+///         "final-newline: missing final newline at line 2, column 2",
+///     ],
+/// );
+/// ```
+pub fn check(contents: &[u8], properties: &Properties) -> Vec<Violation> {
+	let mut checker = Checker::new(contents);
+	let charset = properties.charset();
+	checker.charset(charset);
+	if let Some(Charset::Utf16Be | Charset::Utf16Le) = charset {
+		return checker.violations;
+	}
+	let end_of_line = properties.end_of_line();
+	let indent_style = properties.indent_style();
+	let indent_size = match properties.indent_size() {
+		Some(IndentSize::Columns(columns)) => Some(columns as usize),
+		_ => None,
+	};
+	let trim_trailing_whitespace = properties.trim_trailing_whitespace() == Some(true);
+	let max_line_length = match properties.max_line_length() {
+		Some(MaxLineLength::Columns(columns)) => Some(columns as usize),
+		_ => None,
+	};
+	let tab_width = properties.tab_width().map(|width| width as usize);
+	let tab_stop = tab_stop(properties);
+	for line in 0..checker.lines.len() {
+		let (start, end, next) = checker.lines[line];
+		if let Some(end_of_line) = end_of_line {
+			checker.end_of_line(end, next, end_of_line);
+		}
+		if let Some(indent_style) = indent_style {
+			checker.indentation(start, end, indent_style, indent_size, tab_stop);
+		}
+		if trim_trailing_whitespace {
+			checker.trailing_whitespace(start, end);
+		}
+		if let Some(max_line_length) = max_line_length {
+			checker.line_length(start, end, max_line_length, tab_width);
+		}
+	}
+	if let Some(insert_final_newline) = properties.insert_final_newline() {
+		checker.final_newline(insert_final_newline);
+	}
+	checker.violations
+}
+
+/// Resolves the properties that apply to a file from the config files named
+/// `file_name` above it, and checks its contents against them.
+///
+/// Files that contain a NUL byte are treated as binary, and are not
+/// checked unless their `charset` is UTF-16.
+pub fn check_file<P: AsRef<Path>>(path: P, file_name: &str) -> Result<Vec<Violation>, LookupError> {
+	let path = path.as_ref();
+	let properties = resolve_file_with(path, file_name, Version::CURRENT)?;
+	let contents = fs::read(path).map_err(|source| LookupError::Io {
+		path: path.to_path_buf(),
+		source,
+	})?;
+	let utf16 = matches!(
+		properties.charset(),
+		Some(Charset::Utf16Be | Charset::Utf16Le)
+	);
+	if contents.contains(&0) && !utf16 {
+		return Ok(vec![]);
+	}
+	Ok(check(&contents, &properties))
+}
+
+/// [Checks](check_file) every file in a directory tree, returning the files
+/// with violations in path order.
+///
+/// `.git` directories and symbolic links are skipped.
+///
+/// # Example
+///
+/// ```no_run
+/// for (path, violations) in one_ini::check_tree(".", one_ini::DEFAULT_FILE_NAME).unwrap() {
+///     for violation in violations {
+///         println!("{}: {}", path.display(), violation);
+///     }
+/// }
+/// ```
+pub fn check_tree<P: AsRef<Path>>(
+	root: P,
+	file_name: &str,
+) -> Result<Vec<(PathBuf, Vec<Violation>)>, LookupError> {
+	let mut results = vec![];
+	walk(root.as_ref(), file_name, &mut results)?;
+	Ok(results)
+}
+
+fn walk(
+	path: &Path,
+	file_name: &str,
+	results: &mut Vec<(PathBuf, Vec<Violation>)>,
+) -> Result<(), LookupError> {
+	let io_error = |source| LookupError::Io {
+		path: path.to_path_buf(),
+		source,
+	};
+	let file_type = fs::symlink_metadata(path).map_err(io_error)?.file_type();
+	if file_type.is_file() {
+		let violations = check_file(path, file_name)?;
+		if !violations.is_empty() {
+			results.push((path.to_path_buf(), violations));
+		}
+	} else if file_type.is_dir() {
+		let mut entries = fs::read_dir(path)
+			.and_then(|entries| {
+				entries
+					.map(|entry| Ok(entry?.path()))
+					.collect::<Result<Vec<_>, _>>()
+			})
+			.map_err(io_error)?;
+		entries.sort();
+		for entry in entries {
+			if entry.file_name().is_some_and(|name| name != ".git") {
+				walk(&entry, file_name, results)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The lines of a file and the violations found in them so far.
+struct Checker<'a> {
+	contents: &'a [u8],
+	/// The [lines] of the contents.
+	lines: Vec<(usize, usize, usize)>,
+	violations: Vec<Violation>,
+}
+
+impl<'a> Checker<'a> {
+	fn new(contents: &'a [u8]) -> Self {
+		Checker {
+			contents,
+			lines: lines(contents),
+			violations: vec![],
+		}
+	}
+
+	fn report(&mut self, code: ViolationCode, message: String, offset: usize) {
+		let line = self
+			.lines
+			.partition_point(|&(start, _, _)| start <= offset)
+			.max(1);
+		let start = self.lines.get(line - 1).map_or(0, |&(start, _, _)| start);
+		let column = match offset > start {
+			true => chars(&self.contents[start..offset]).count() + 1,
+			false => 1,
+		};
+		self.violations.push(Violation {
+			code,
+			message,
+			position: Position {
+				offset,
+				line,
+				column,
+			},
+		});
+	}
+
+	fn charset(&mut self, charset: Option<Charset>) {
+		let contents = self.contents;
+		let has_bom = contents.starts_with(BOM);
+		let utf8 = match charset {
+			Some(Charset::Utf8) if has_bom => {
+				let message = String::from("byte order mark, expected `utf-8` without one");
+				self.report(ViolationCode::Charset, message, 0);
+				true
+			}
+			Some(Charset::Utf8Bom) if !has_bom => {
+				let message = String::from("missing byte order mark, expected `utf-8-bom`");
+				self.report(ViolationCode::Charset, message, 0);
+				true
+			}
+			Some(Charset::Utf8 | Charset::Utf8Bom) => true,
+			Some(Charset::Latin1) if has_bom => {
+				let message = String::from("UTF-8 byte order mark, expected `latin1`");
+				self.report(ViolationCode::Charset, message, 0);
+				false
+			}
+			Some(charset @ Charset::Utf16Be) if !contents.starts_with(b"\xfe\xff") => {
+				self.missing_utf16_bom(charset);
+				false
+			}
+			Some(charset @ Charset::Utf16Le) if !contents.starts_with(b"\xff\xfe") => {
+				self.missing_utf16_bom(charset);
+				false
+			}
+			_ => false,
+		};
+		if utf8 {
+			if let Err(e) = std::str::from_utf8(contents) {
+				let message = format!("invalid UTF-8, expected `{}`", charset.unwrap());
+				self.report(ViolationCode::Charset, message, e.valid_up_to());
+			}
+		}
+	}
+
+	fn missing_utf16_bom(&mut self, charset: Charset) {
+		let message = format!("missing byte order mark, expected `{}`", charset);
+		self.report(ViolationCode::Charset, message, 0);
+	}
+
+	fn end_of_line(&mut self, end: usize, next: usize, expected: EndOfLine) {
+		let found = match &self.contents[end..next] {
+			b"\n" => EndOfLine::Lf,
+			b"\r" => EndOfLine::Cr,
+			b"\r\n" => EndOfLine::Crlf,
+			_ => return,
+		};
+		if found != expected {
+			let message = format!("line ends with `{}`, expected `{}`", found, expected);
+			self.report(ViolationCode::EndOfLine, message, end);
+		}
+	}
+
+	fn indentation(
+		&mut self,
+		start: usize,
+		end: usize,
+		style: IndentStyle,
+		size: Option<usize>,
+		tab_stop: Option<usize>,
+	) {
+		let line = &self.contents[start..end];
+		let indent = line
+			.iter()
+			.take_while(|&&b| b == b' ' || b == b'\t')
+			.count();
+		// Whitespace-only lines are left to `trim_trailing_whitespace`.
+		if indent == line.len() {
+			return;
+		}
+		let indent_bytes = &line[..indent];
+		match style {
+			IndentStyle::Space => {
+				if let Some(tab) = indent_bytes.iter().position(|&b| b == b'\t') {
+					let message = String::from("indented with a tab, expected spaces");
+					self.report(ViolationCode::IndentStyle, message, start + tab);
+					return;
+				}
+				let Some(size) = size else {
+					return;
+				};
+				let continues_comment = line[indent] == b'*' && indent % size == 1;
+				if indent % size != 0 && !continues_comment {
+					let message = format!(
+						"indented by {} spaces, expected a multiple of {}",
+						indent, size
+					);
+					self.report(ViolationCode::IndentSize, message, start + indent);
+				}
+			}
+			IndentStyle::Tab => {
+				if let Some(space) = indenting_space(indent_bytes, tab_stop) {
+					let message = String::from("indented with spaces, expected tabs");
+					self.report(ViolationCode::IndentStyle, message, start + space);
+				}
+			}
+		}
+	}
+
+	fn trailing_whitespace(&mut self, start: usize, end: usize) {
+		let line = &self.contents[start..end];
+		let content_end = line
+			.iter()
+			.rposition(|&b| b != b' ' && b != b'\t')
+			.map_or(0, |i| i + 1);
+		if content_end < line.len() {
+			let message = String::from("trailing whitespace");
+			self.report(
+				ViolationCode::TrailingWhitespace,
+				message,
+				start + content_end,
+			);
+		}
+	}
+
+	fn line_length(&mut self, start: usize, end: usize, max: usize, tab_width: Option<usize>) {
+		let mut width = 0;
+		let mut overflow = None;
+		for (offset, c) in chars(&self.contents[start..end]) {
+			width = match (c, tab_width) {
+				('\t', Some(tab_width)) if tab_width > 0 => (width / tab_width + 1) * tab_width,
+				_ => width + 1,
+			};
+			if width > max && overflow.is_none() {
+				overflow = Some(start + offset);
+			}
+		}
+		if let Some(offset) = overflow {
+			let message = format!(
+				"line is {} columns long, more than the `max_line_length` of {}",
+				width, max
+			);
+			self.report(ViolationCode::MaxLineLength, message, offset);
+		}
+	}
+
+	fn final_newline(&mut self, expected: bool) {
+		let Some(&(_, end, next)) = self.lines.last() else {
+			return;
+		};
+		match (expected, end < next) {
+			(true, false) => {
+				let message = String::from("missing final newline");
+				self.report(ViolationCode::FinalNewline, message, end);
+			}
+			(false, true) => {
+				let message = String::from("final newline, expected none");
+				self.report(ViolationCode::FinalNewline, message, end);
+			}
+			_ => (),
+		}
+	}
+}
+
+/// The number of columns a tab advances to a multiple of in tab indentation:
+/// the `indent_size`, or the `tab_width` when it is `tab` or not set.
+pub(crate) fn tab_stop(properties: &Properties) -> Option<usize> {
+	let tab_stop = match properties.indent_size() {
+		Some(IndentSize::Columns(columns)) => columns,
+		_ => properties.tab_width()?,
+	};
+	match tab_stop {
+		0 => None,
+		tab_stop => Some(tab_stop as usize),
+	}
+}
+
+/// The offset of the first space that indents rather than aligns in tab
+/// indentation. Spaces align when they follow every tab and are fewer than
+/// the tab stop, or follow a tab when the tab stop is not known.
+fn indenting_space(indent: &[u8], tab_stop: Option<usize>) -> Option<usize> {
+	let tabs_end = indent
+		.iter()
+		.rposition(|&b| b == b'\t')
+		.map_or(0, |tab| tab + 1);
+	if let Some(space) = indent[..tabs_end].iter().position(|&b| b == b' ') {
+		return Some(space);
+	}
+	let spaces = indent.len() - tabs_end;
+	let aligns = match tab_stop {
+		Some(tab_stop) => spaces < tab_stop,
+		None => tabs_end > 0,
+	};
+	match spaces == 0 || aligns {
+		true => None,
+		false => Some(tabs_end),
+	}
+}
+
+/// The start of the content, the end of the content and the start of the
+/// next line, of each line. `\n`, `\r\n` and `\r` all end a line, and the
+/// first line starts after a byte order mark.
+pub(crate) fn lines(contents: &[u8]) -> Vec<(usize, usize, usize)> {
+	let mut lines = vec![];
+	let mut start = match contents.starts_with(BOM) {
+		true => BOM.len(),
+		false => 0,
+	};
+	let mut i = start;
+	while i < contents.len() {
+		let next = match (contents[i], contents.get(i + 1)) {
+			(b'\r', Some(b'\n')) => i + 2,
+			(b'\r' | b'\n', _) => i + 1,
+			_ => {
+				i += 1;
+				continue;
+			}
+		};
+		lines.push((start, i, next));
+		(start, i) = (next, next);
+	}
+	if start < contents.len() {
+		lines.push((start, contents.len(), contents.len()));
+	}
+	lines
+}
+
+/// The characters of a line and their byte offsets, with each invalid UTF-8
+/// sequence counting as one character.
+fn chars(bytes: &[u8]) -> impl Iterator<Item = (usize, char)> + '_ {
+	let mut offset = 0;
+	bytes.utf8_chunks().flat_map(move |chunk| {
+		let start = offset;
+		offset += chunk.valid().len() + chunk.invalid().len();
+		let invalid = match chunk.invalid().is_empty() {
+			true => None,
+			false => Some((start + chunk.valid().len(), char::REPLACEMENT_CHARACTER)),
+		};
+		chunk
+			.valid()
+			.char_indices()
+			.map(move |(i, c)| (start + i, c))
+			.chain(invalid)
+	})
+}
@@ -0,0 +1,459 @@
+//! # Deserializing
+//!
+//! Reads INI contents into Rust types with [serde](https://serde.rs/). The
+//! prelude maps to the top-level fields and each section to a nested struct
+//! or map, so a file reads as a map of maps.
+
+use crate::{parse, Item, ParseError, Span};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::{error, fmt};
+
+/// An error raised by [from_str].
+#[derive(Debug)]
+pub enum DeserializeError {
+	/// The contents could not be parsed.
+	Parse(ParseError),
+	/// The contents do not match the type, such as a value that is not a
+	/// valid number or a missing field.
+	Invalid {
+		message: String,
+		/// The value or section header the problem was found in, if it is
+		/// known.
+		span: Option<Span>,
+	},
+}
+
+impl DeserializeError {
+	fn invalid(message: String, span: Span) -> Self {
+		DeserializeError::Invalid {
+			message,
+			span: Some(span),
+		}
+	}
+
+	/// Locates an error that was raised without a location.
+	fn at(self, span: Option<Span>) -> Self {
+		match self {
+			DeserializeError::Invalid {
+				message,
+				span: None,
+			} => DeserializeError::Invalid { message, span },
+			error => error,
+		}
+	}
+}
+
+impl fmt::Display for DeserializeError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			DeserializeError::Parse(error) => error.fmt(formatter),
+			DeserializeError::Invalid { message, span } => {
+				formatter.write_str(message)?;
+				if let Some(span) = span {
+					write!(
+						formatter,
+						" at line {}, column {}",
+						span.start.line, span.start.column
+					)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+impl error::Error for DeserializeError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			DeserializeError::Parse(error) => Some(error),
+			DeserializeError::Invalid { .. } => None,
+		}
+	}
+}
+
+impl de::Error for DeserializeError {
+	fn custom<T: fmt::Display>(message: T) -> Self {
+		DeserializeError::Invalid {
+			message: message.to_string(),
+			span: None,
+		}
+	}
+}
+
+/// Deserializes an instance of `T` from INI contents.
+///
+/// The prelude's pairs and the sections are the entries of the top-level
+/// map, keyed by key and by section name. Each section is itself a map of
+/// its pairs. Sections with the same name are merged, and a key set more
+/// than once in a map takes its last value. Comments are skipped.
+///
+/// Values are strings, which deserialize into:
+///
+/// - `bool`s, from `true` or `false` in any case
+/// - numbers and `char`s, as they are parsed by [`str::parse`]
+/// - unit enum variants, by name
+/// - `Option`s, as `None` from an empty value
+/// - sequences, by splitting on `,` and trimming the whitespace around each
+///   item
+///
+/// Strings borrow from the contents where the type allows it. Fields marked
+/// `#[serde(flatten)]` are buffered by serde as strings and maps before they
+/// are deserialized, so they can only contain strings.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Level {
+///     Debug,
+///     Info,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Service {
+///     port: u16,
+///     tls: bool,
+///     hosts: Vec<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config<'a> {
+///     name: &'a str,
+///     level: Level,
+///     http: Service,
+/// }
+///
+/// let contents = "name = api\nlevel = info\n\n[http]\nport = 8080\ntls = TRUE\nhosts = a, b\n";
+/// let config: Config = one_ini::from_str(contents).unwrap();
+///
+/// assert_eq!(config.name, "api");
+/// assert_eq!(config.level, Level::Info);
+/// assert_eq!(config.http.port, 8080);
+/// assert!(config.http.tls);
+/// assert_eq!(config.http.hosts, ["a", "b"]);
+///
+/// let services: HashMap<String, Service> = one_ini::from_str("[a]\nport=1\ntls=false\nhosts=\n").unwrap();
+/// assert_eq!(services["a"].port, 1);
+/// ```
+pub fn from_str<'de, T: Deserialize<'de>>(contents: &'de str) -> Result<T, DeserializeError> {
+	let ast = parse(contents).map_err(DeserializeError::Parse)?;
+	let mut root = Table::default();
+	for item in &ast.body {
+		match item {
+			Item::Pair(pair) => {
+				let (key_span, value_span) = (pair.key_span.unwrap(), pair.value_span.unwrap());
+				let value = Node::Value(slice(contents, value_span), value_span);
+				root.insert(slice(contents, key_span), key_span, value);
+			}
+			Item::Section(section) => {
+				let name_span = section.name_span.unwrap();
+				let mut table = Table::default();
+				for item in &section.body {
+					if let Item::Pair(pair) = item {
+						let (key_span, value_span) =
+							(pair.key_span.unwrap(), pair.value_span.unwrap());
+						let value = Node::Value(slice(contents, value_span), value_span);
+						table.insert(slice(contents, key_span), key_span, value);
+					}
+				}
+				root.insert(
+					slice(contents, name_span),
+					name_span,
+					Node::Table(table, name_span),
+				);
+			}
+			_ => (),
+		}
+	}
+	T::deserialize(TableDeserializer { table: &root })
+}
+
+fn slice(contents: &str, span: Span) -> &str {
+	&contents[span.start.offset..span.end.offset]
+}
+
+/// A value or a section, with where it was parsed from.
+enum Node<'de> {
+	Value(&'de str, Span),
+	Table(Table<'de>, Span),
+}
+
+impl Node<'_> {
+	fn span(&self) -> Span {
+		match self {
+			Node::Value(_, span) | Node::Table(_, span) => *span,
+		}
+	}
+}
+
+/// The entries of the prelude or a section, in the order they first appear
+/// in.
+#[derive(Default)]
+struct Table<'de> {
+	entries: Vec<(&'de str, Span, Node<'de>)>,
+}
+
+impl<'de> Table<'de> {
+	fn insert(&mut self, key: &'de str, key_span: Span, node: Node<'de>) {
+		let Some(index) = self.entries.iter().position(|(k, _, _)| *k == key) else {
+			self.entries.push((key, key_span, node));
+			return;
+		};
+		match (&mut self.entries[index].2, node) {
+			(Node::Table(table, _), Node::Table(other, _)) => {
+				for (key, key_span, node) in other.entries {
+					table.insert(key, key_span, node);
+				}
+			}
+			(existing, node) => *existing = node,
+		}
+	}
+}
+
+struct TableDeserializer<'a, 'de> {
+	table: &'a Table<'de>,
+}
+
+impl<'de> de::Deserializer<'de> for TableDeserializer<'_, 'de> {
+	type Error = DeserializeError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_map(Entries {
+			entries: self.table.entries.iter(),
+			node: None,
+		})
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+		identifier ignored_any
+	}
+}
+
+struct Entries<'a, 'de> {
+	entries: std::slice::Iter<'a, (&'de str, Span, Node<'de>)>,
+	node: Option<&'a Node<'de>>,
+}
+
+impl<'de> MapAccess<'de> for Entries<'_, 'de> {
+	type Error = DeserializeError;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, Self::Error> {
+		let Some((key, key_span, node)) = self.entries.next() else {
+			return Ok(None);
+		};
+		self.node = Some(node);
+		seed.deserialize(ValueDeserializer {
+			value: key,
+			span: *key_span,
+		})
+		.map(Some)
+		.map_err(|e| e.at(Some(*key_span)))
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(
+		&mut self,
+		seed: V,
+	) -> Result<V::Value, Self::Error> {
+		// Serde calls `next_key_seed` first.
+		let node = self.node.take().unwrap();
+		let result = match node {
+			Node::Value(value, span) => seed.deserialize(ValueDeserializer { value, span: *span }),
+			Node::Table(table, _) => seed.deserialize(TableDeserializer { table }),
+		};
+		result.map_err(|e| e.at(Some(node.span())))
+	}
+}
+
+/// Deserializes a key or value.
+#[derive(Clone, Copy)]
+struct ValueDeserializer<'de> {
+	value: &'de str,
+	span: Span,
+}
+
+impl ValueDeserializer<'_> {
+	fn invalid(&self, expected: &str) -> DeserializeError {
+		DeserializeError::invalid(
+			format!("`{}` is not a valid {}", self.value, expected),
+			self.span,
+		)
+	}
+}
+
+macro_rules! deserialize_parsed {
+	($($method:ident => $visit:ident,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				match self.value.parse() {
+					Ok(value) => visitor.$visit(value),
+					Err(_) => Err(self.invalid(stringify!($visit).trim_start_matches("visit_"))),
+				}
+			}
+		)*
+	};
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+	type Error = DeserializeError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_borrowed_str(self.value)
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value.to_lowercase().as_str() {
+			"true" => visitor.visit_bool(true),
+			"false" => visitor.visit_bool(false),
+			_ => Err(self.invalid("bool, expected `true` or `false`")),
+		}
+	}
+
+	deserialize_parsed! {
+		deserialize_i8 => visit_i8,
+		deserialize_i16 => visit_i16,
+		deserialize_i32 => visit_i32,
+		deserialize_i64 => visit_i64,
+		deserialize_i128 => visit_i128,
+		deserialize_u8 => visit_u8,
+		deserialize_u16 => visit_u16,
+		deserialize_u32 => visit_u32,
+		deserialize_u64 => visit_u64,
+		deserialize_u128 => visit_u128,
+		deserialize_f32 => visit_f32,
+		deserialize_f64 => visit_f64,
+		deserialize_char => visit_char,
+	}
+
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_borrowed_bytes(self.value.as_bytes())
+	}
+
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_bytes(visitor)
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value.is_empty() {
+			true => visitor.visit_none(),
+			false => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value.is_empty() {
+			true => visitor.visit_unit(),
+			false => Err(self.invalid("unit, expected an empty value")),
+		}
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.deserialize_unit(visitor)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let items = match self.value.is_empty() {
+			true => None,
+			false => Some(self.value.split(',')),
+		};
+		visitor.visit_seq(Items {
+			items: items.into_iter().flatten(),
+			span: self.span,
+		})
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(
+		self,
+		_len: usize,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_len: usize,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor
+			.visit_enum(BorrowedStrDeserializer::new(self.value))
+			.map_err(|e: DeserializeError| e.at(Some(self.span)))
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	forward_to_deserialize_any! {
+		str string map struct identifier
+	}
+}
+
+/// The comma-separated items of a value.
+struct Items<'de, I: Iterator<Item = &'de str>> {
+	items: I,
+	span: Span,
+}
+
+impl<'de, I: Iterator<Item = &'de str>> SeqAccess<'de> for Items<'de, I> {
+	type Error = DeserializeError;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		match self.items.next() {
+			Some(item) => seed
+				.deserialize(ValueDeserializer {
+					value: item.trim_matches([' ', '\t']),
+					span: self.span,
+				})
+				.map(Some),
+			None => Ok(None),
+		}
+	}
+}
@@ -0,0 +1,594 @@
+//! # Lossless documents
+//!
+//! A concrete syntax tree that keeps every byte of the parsed contents,
+//! including whitespace, blank lines, the original newlines and the BOM, so
+//! that a [`Document`] serializes back to exactly its input.
+
+use crate::{Comment, INIParser, Item, OneINIAST, Pair, ParseError, Rule, Section};
+use pest::iterators::Pair as PestPair;
+use pest::Parser;
+use std::{error, fmt};
+
+const BOM: char = '\u{feff}';
+
+/// Parses [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+/// contents into a lossless [`Document`].
+///
+/// # Example
+///
+/// ```
+/// let contents = "\u{feff}root = true\r\n\r\n  [*]  \r\nkey =  value\t\r\n";
+/// let document = one_ini::parse_document(contents).unwrap();
+///
+/// assert_eq!(document.to_string(), contents);
+/// assert_eq!(document.to_ast().to_string(), "root=true\n\n[*]\nkey=value\n");
+/// ```
+pub fn parse_document(contents: &str) -> Result<Document, ParseError> {
+	let mut parsed =
+		INIParser::parse(Rule::ini, contents).map_err(|e| ParseError::from_pest(e, contents))?;
+	let mut builder = Builder {
+		input: contents,
+		pos: 0,
+		document: Document::default(),
+	};
+	if contents.starts_with(BOM) {
+		builder.document.bom = true;
+		builder.pos = BOM.len_utf8();
+	}
+	builder.visit(parsed.next().unwrap());
+	Ok(builder.finish())
+}
+
+/// The root of a lossless tree: the lines of the prelude followed by any
+/// number of sections.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+	/// Whether the contents start with a UTF-8 byte order mark.
+	pub bom: bool,
+	/// The lines before the first section header.
+	pub prelude: Vec<Line>,
+	pub sections: Vec<DocumentSection>,
+}
+
+/// A section header line and every line up to the next header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentSection {
+	/// A line whose content is always a [header](LineContent::Header).
+	pub header: Line,
+	pub body: Vec<Line>,
+}
+
+/// A single line, split into its content and the surrounding trivia.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{Line, LineContent};
+///
+/// let line = Line {
+///     indent: String::from("  "),
+///     content: LineContent::Pair {
+///         key: String::from("key"),
+///         before_equals: String::from(" "),
+///         after_equals: String::from("  "),
+///         value: String::from("value"),
+///     },
+///     trailing: String::from("\t"),
+///     newline: String::from("\r\n"),
+/// };
+///
+/// assert_eq!(line.to_string(), "  key =  value\t\r\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+	/// The whitespace before the content.
+	pub indent: String,
+	pub content: LineContent,
+	/// The whitespace between the content and the newline. Comments own
+	/// their trailing whitespace, so this is always empty for them.
+	pub trailing: String,
+	/// The line separator (`\n`, `\r\n` or `\r`), or an empty string for a
+	/// last line that does not end with one.
+	pub newline: String,
+}
+
+/// What a [`Line`] contains, once its trivia is removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LineContent {
+	/// An empty or whitespace-only line.
+	Blank,
+	Comment {
+		indicator: char,
+		value: String,
+	},
+	Pair {
+		key: String,
+		/// The whitespace between the key and the `=`.
+		before_equals: String,
+		/// The whitespace between the `=` and the value.
+		after_equals: String,
+		value: String,
+	},
+	/// A section header, where `name` is the part between `[` and `]`.
+	Header {
+		name: String,
+	},
+}
+
+impl Document {
+	/// Drops the trivia, keeping only the items an [`OneINIAST`] describes.
+	/// The items of the returned AST have no spans.
+	pub fn to_ast(&self) -> OneINIAST {
+		let mut body = to_items(&self.prelude);
+		for section in &self.sections {
+			if let LineContent::Header { name } = &section.header.content {
+				body.push(Item::Section(Section {
+					name: name.clone(),
+					body: to_items(&section.body),
+					..Default::default()
+				}));
+			}
+		}
+		OneINIAST::new(body)
+	}
+
+	/// Sets `key` to `value` in the last section named `section_glob`,
+	/// returning the previous value if the key was already set there.
+	///
+	/// Only the value of an existing pair is replaced. A missing pair is
+	/// added after the last non-blank line of the section, and a missing
+	/// section is appended to the end of the document; new lines copy the
+	/// indentation, spacing around `=` and line separator already in use.
+	/// Every other byte is left alone.
+	///
+	/// # Errors
+	///
+	/// A section name, key or value that would not parse back to itself, such
+	/// as one with a line break or a key with `=`, is rejected without
+	/// changing the document.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let mut document = one_ini::parse_document("[*]\r\n  indent_size = 2\r\n\r\n[*.md]\r\n").unwrap();
+	///
+	/// assert_eq!(document.set_property("*", "indent_size", "4"), Ok(Some(String::from("2"))));
+	/// document.set_property("*", "indent_style", "space").unwrap();
+	/// document.set_property("*.rs", "indent_size", "4").unwrap();
+	/// assert!(document.set_property("*", "charset", "utf-8\n[*]").is_err());
+	///
+	/// assert_eq!(
+	///     document.to_string(),
+	///     "[*]\r\n  indent_size = 4\r\n  indent_style = space\r\n\r\n[*.md]\r\n\r\n[*.rs]\r\n  indent_size = 4\r\n",
+	/// );
+	/// ```
+	pub fn set_property(
+		&mut self,
+		section_glob: &str,
+		key: &str,
+		value: &str,
+	) -> Result<Option<String>, SetPropertyError> {
+		Section::check_name(section_glob)
+			.and_then(|()| Pair::check(key, value))
+			.map_err(|message| SetPropertyError { message })?;
+		let template = self.pair_template(section_glob);
+		let newline = self.newline();
+		let section = match self.sections.iter().rposition(|s| s.is_named(section_glob)) {
+			Some(index) => &mut self.sections[index],
+			None => self.push_section(section_glob),
+		};
+		for line in section.body.iter_mut().rev() {
+			if let LineContent::Pair {
+				key: k, value: v, ..
+			} = &mut line.content
+			{
+				if k.eq_ignore_ascii_case(key) {
+					return Ok(Some(std::mem::replace(v, String::from(value))));
+				}
+			}
+		}
+		let index = section
+			.body
+			.iter()
+			.rposition(|line| line.content != LineContent::Blank)
+			.map_or(0, |i| i + 1);
+		let previous = match index {
+			0 => &mut section.header,
+			_ => &mut section.body[index - 1],
+		};
+		// Keep a missing final newline at the end of the file.
+		let newline = if previous.newline.is_empty() {
+			std::mem::replace(&mut previous.newline, newline)
+		} else {
+			newline
+		};
+		section.body.insert(
+			index,
+			Line {
+				indent: template.indent,
+				content: LineContent::Pair {
+					key: String::from(key),
+					before_equals: template.before_equals,
+					after_equals: template.after_equals,
+					value: String::from(value),
+				},
+				trailing: String::new(),
+				newline,
+			},
+		);
+		Ok(None)
+	}
+
+	/// Removes every `key` pair from the sections named `section_glob`,
+	/// returning `true` if any was found. Only the lines of those pairs are
+	/// removed.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let mut document = one_ini::parse_document("[*]\ncharset = utf-8\n# keep me\nend_of_line = lf\n").unwrap();
+	///
+	/// assert!(document.remove_property("*", "charset"));
+	/// assert!(!document.remove_property("*", "charset"));
+	/// assert_eq!(document.to_string(), "[*]\n# keep me\nend_of_line = lf\n");
+	/// ```
+	pub fn remove_property(&mut self, section_glob: &str, key: &str) -> bool {
+		let mut removed = false;
+		for section in self
+			.sections
+			.iter_mut()
+			.filter(|s| s.is_named(section_glob))
+		{
+			section.body.retain(|line| match &line.content {
+				LineContent::Pair { key: k, .. } if k.eq_ignore_ascii_case(key) => {
+					removed = true;
+					false
+				}
+				_ => true,
+			});
+		}
+		removed
+	}
+
+	/// Renames every section named `from` to `to`, returning `true` if any
+	/// was found. Only the names between the brackets change.
+	///
+	/// # Errors
+	///
+	/// A name that would not parse back to itself, such as an empty one or
+	/// one with a line break, is rejected without changing the document.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let mut document = one_ini::parse_document("  [*.js]  \nindent_size = 2\n").unwrap();
+	///
+	/// assert_eq!(document.rename_section("*.js", "*.{js,ts}"), Ok(true));
+	/// assert_eq!(document.to_string(), "  [*.{js,ts}]  \nindent_size = 2\n");
+	/// ```
+	pub fn rename_section(&mut self, from: &str, to: &str) -> Result<bool, SetPropertyError> {
+		Section::check_name(to).map_err(|message| SetPropertyError { message })?;
+		let mut renamed = false;
+		for section in self.sections.iter_mut().filter(|s| s.is_named(from)) {
+			section.header.content = LineContent::Header {
+				name: String::from(to),
+			};
+			renamed = true;
+		}
+		Ok(renamed)
+	}
+
+	/// The first line separator in use, or `\n` if there is none.
+	fn newline(&self) -> String {
+		self.lines()
+			.map(|line| &line.newline)
+			.find(|newline| !newline.is_empty())
+			.map_or_else(|| String::from("\n"), String::clone)
+	}
+
+	/// Iterates over every line, in document order.
+	fn lines(&self) -> impl DoubleEndedIterator<Item = &Line> {
+		self.prelude.iter().chain(
+			self.sections
+				.iter()
+				.flat_map(|s| std::iter::once(&s.header).chain(&s.body)),
+		)
+	}
+
+	/// Finds the last pair in the section named `section_glob`, or else in
+	/// the whole document, to copy the formatting of new pairs from.
+	fn pair_template(&self, section_glob: &str) -> PairTemplate {
+		let in_section = self
+			.sections
+			.iter()
+			.filter(|s| s.is_named(section_glob))
+			.flat_map(|s| &s.body);
+		in_section
+			.rev()
+			.chain(self.lines().rev())
+			.find_map(|line| match &line.content {
+				LineContent::Pair {
+					before_equals,
+					after_equals,
+					..
+				} => Some(PairTemplate {
+					indent: line.indent.clone(),
+					before_equals: before_equals.clone(),
+					after_equals: after_equals.clone(),
+				}),
+				_ => None,
+			})
+			.unwrap_or_default()
+	}
+
+	/// Appends an empty section, separated from any previous line by a
+	/// blank line.
+	fn push_section(&mut self, name: &str) -> &mut DocumentSection {
+		let newline = self.newline();
+		let last = match self.sections.last_mut() {
+			Some(section) => Some(section.body.last_mut().unwrap_or(&mut section.header)),
+			None => self.prelude.last_mut(),
+		};
+		let mut needs_blank = false;
+		if let Some(last) = last {
+			if last.newline.is_empty() {
+				last.newline = newline.clone();
+			}
+			needs_blank = last.content != LineContent::Blank;
+		}
+		if needs_blank {
+			let blank = Line {
+				indent: String::new(),
+				content: LineContent::Blank,
+				trailing: String::new(),
+				newline: newline.clone(),
+			};
+			match self.sections.last_mut() {
+				Some(section) => section.body.push(blank),
+				None => self.prelude.push(blank),
+			}
+		}
+		self.sections.push(DocumentSection {
+			header: Line {
+				indent: String::new(),
+				content: LineContent::Header {
+					name: String::from(name),
+				},
+				trailing: String::new(),
+				newline,
+			},
+			body: vec![],
+		});
+		self.sections.last_mut().unwrap()
+	}
+}
+
+/// An error raised by [`Document::set_property`] and
+/// [`Document::rename_section`] for a section name, key or value that would
+/// not parse back to itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetPropertyError {
+	message: &'static str,
+}
+
+impl fmt::Display for SetPropertyError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(self.message)
+	}
+}
+
+impl error::Error for SetPropertyError {}
+
+/// The formatting new pairs are created with.
+#[derive(Default)]
+struct PairTemplate {
+	indent: String,
+	before_equals: String,
+	after_equals: String,
+}
+
+impl DocumentSection {
+	fn is_named(&self, name: &str) -> bool {
+		matches!(&self.header.content, LineContent::Header { name: n } if n == name)
+	}
+}
+
+impl fmt::Display for Document {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		if self.bom {
+			write!(formatter, "{}", BOM)?;
+		}
+		for line in &self.prelude {
+			line.fmt(formatter)?;
+		}
+		for section in &self.sections {
+			section.fmt(formatter)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for DocumentSection {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		self.header.fmt(formatter)?;
+		for line in &self.body {
+			line.fmt(formatter)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Line {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(&self.indent)?;
+		match &self.content {
+			LineContent::Blank => (),
+			LineContent::Comment { indicator, value } => {
+				write!(formatter, "{}{}", indicator, value)?
+			}
+			LineContent::Pair {
+				key,
+				before_equals,
+				after_equals,
+				value,
+			} => write!(
+				formatter,
+				"{}{}={}{}",
+				key, before_equals, after_equals, value
+			)?,
+			LineContent::Header { name } => write!(formatter, "[{}]", name)?,
+		}
+		write!(formatter, "{}{}", self.trailing, self.newline)
+	}
+}
+
+fn to_items(lines: &[Line]) -> Vec<Item> {
+	lines
+		.iter()
+		.filter_map(|line| match &line.content {
+			LineContent::Comment { indicator, value } => Some(Item::Comment(Comment {
+				indicator: *indicator,
+				value: value.clone(),
+				..Default::default()
+			})),
+			LineContent::Pair { key, value, .. } => Some(Item::Pair(Pair {
+				key: key.clone(),
+				value: value.clone(),
+				..Default::default()
+			})),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Walks the pest tree in document order, attributing the bytes between its
+/// tokens to the lines they belong to.
+struct Builder<'a> {
+	input: &'a str,
+	pos: usize,
+	document: Document,
+}
+
+impl<'a> Builder<'a> {
+	fn visit(&mut self, pair: PestPair<'a, Rule>) {
+		for child in pair.into_inner() {
+			match child.as_rule() {
+				Rule::section => {
+					let mut inner_rules = child.into_inner();
+					let header = inner_rules.next().unwrap();
+					let start = header.as_span().start();
+					let name = header.into_inner().next().unwrap().as_str();
+					let indent = self.blank_lines_until(start);
+					self.pos = start + name.len() + 2;
+					let header = self.finish_line(
+						indent,
+						LineContent::Header {
+							name: String::from(name),
+						},
+					);
+					self.document.sections.push(DocumentSection {
+						header,
+						body: vec![],
+					});
+					self.visit(inner_rules.next().unwrap());
+				}
+				Rule::pair => {
+					let mut inner_rules = child.into_inner();
+					let key = inner_rules.next().unwrap().as_span();
+					let value = inner_rules.next().unwrap().as_span();
+					let indent = self.blank_lines_until(key.start());
+					let between = &self.input[key.end()..value.start()];
+					let equals = between.find('=').unwrap();
+					self.pos = value.end();
+					let line = self.finish_line(
+						indent,
+						LineContent::Pair {
+							key: String::from(key.as_str()),
+							before_equals: String::from(&between[..equals]),
+							after_equals: String::from(&between[equals + 1..]),
+							value: String::from(value.as_str()),
+						},
+					);
+					self.push(line);
+				}
+				Rule::comment => {
+					let mut inner_rules = child.into_inner();
+					let indicator = inner_rules.next().unwrap().as_span();
+					let value = inner_rules.next().unwrap().as_span();
+					let indent = self.blank_lines_until(indicator.start());
+					self.pos = value.end();
+					let line = self.finish_line(
+						indent,
+						LineContent::Comment {
+							indicator: indicator.as_str().chars().next().unwrap(),
+							value: String::from(value.as_str()),
+						},
+					);
+					self.push(line);
+				}
+				Rule::EOI => (),
+				_ => unreachable!(),
+			}
+		}
+	}
+
+	/// Records the blank lines between the current position and `start`,
+	/// returning the indentation of the line `start` is on.
+	fn blank_lines_until(&mut self, start: usize) -> String {
+		loop {
+			let rest = &self.input[self.pos..start];
+			match rest.find(['\r', '\n']) {
+				Some(end) => {
+					let indent = String::from(&rest[..end]);
+					self.pos += end;
+					let line = self.finish_line(indent, LineContent::Blank);
+					self.push(line);
+				}
+				None => {
+					self.pos = start;
+					return String::from(rest);
+				}
+			}
+		}
+	}
+
+	/// Completes a line whose content ends at the current position with its
+	/// trailing whitespace and newline.
+	fn finish_line(&mut self, indent: String, content: LineContent) -> Line {
+		let rest = &self.input[self.pos..];
+		let trailing = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+		let rest = &rest[trailing..];
+		let newline = if rest.starts_with("\r\n") {
+			2
+		} else if rest.starts_with(['\r', '\n']) {
+			1
+		} else {
+			0
+		};
+		let line = Line {
+			indent,
+			content,
+			trailing: String::from(&self.input[self.pos..self.pos + trailing]),
+			newline: String::from(&rest[..newline]),
+		};
+		self.pos += trailing + newline;
+		line
+	}
+
+	fn push(&mut self, line: Line) {
+		match self.document.sections.last_mut() {
+			Some(section) => section.body.push(line),
+			None => self.document.prelude.push(line),
+		}
+	}
+
+	/// Consumes the trailing blank lines.
+	fn finish(mut self) -> Document {
+		let indent = self.blank_lines_until(self.input.len());
+		if !indent.is_empty() {
+			let line = self.finish_line(indent, LineContent::Blank);
+			self.push(line);
+		}
+		self.document
+	}
+}
@@ -0,0 +1,137 @@
+//! # Offset encodings
+//!
+//! Conversions of UTF-8 byte offsets into the units other languages index
+//! strings by, such as the UTF-16 code units of JavaScript strings.
+
+use crate::span::clamp;
+use std::{error, fmt, str};
+
+/// The unit offsets are counted in.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::OffsetEncoding;
+///
+/// let contents = "é=😀\n";
+/// let end = contents.len() - 1;
+///
+/// assert_eq!(OffsetEncoding::Utf8.offset(contents, end), 7);
+/// assert_eq!(OffsetEncoding::Utf16.offset(contents, end), 4);
+/// assert_eq!(OffsetEncoding::CodePoint.offset(contents, end), 3);
+/// assert_eq!("utf16".parse(), Ok(OffsetEncoding::Utf16));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OffsetEncoding {
+	/// UTF-8 bytes, the offsets of Rust strings and Node `Buffer`s.
+	#[default]
+	Utf8,
+	/// UTF-16 code units, the offsets of JavaScript strings and of LSP
+	/// positions.
+	Utf16,
+	/// Unicode code points, the offsets of Python strings and of
+	/// `Array.from(string)`.
+	CodePoint,
+}
+
+impl OffsetEncoding {
+	/// The length of a character in this encoding.
+	pub fn char_len(self, c: char) -> usize {
+		match self {
+			OffsetEncoding::Utf8 => c.len_utf8(),
+			OffsetEncoding::Utf16 => c.len_utf16(),
+			OffsetEncoding::CodePoint => 1,
+		}
+	}
+
+	/// Converts a byte offset into the contents, which must be on a character
+	/// boundary, into an offset in this encoding.
+	pub fn offset(self, contents: &str, offset: usize) -> usize {
+		match self {
+			OffsetEncoding::Utf8 => offset,
+			_ => contents[..offset].chars().map(|c| self.char_len(c)).sum(),
+		}
+	}
+}
+
+impl fmt::Display for OffsetEncoding {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(match self {
+			OffsetEncoding::Utf8 => "utf8",
+			OffsetEncoding::Utf16 => "utf16",
+			OffsetEncoding::CodePoint => "codepoint",
+		})
+	}
+}
+
+/// An error raised for an encoding other than `utf8`, `utf16` or
+/// `codepoint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOffsetEncodingError {
+	encoding: String,
+}
+
+impl fmt::Display for ParseOffsetEncodingError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			formatter,
+			"invalid offset encoding `{}`, expected `utf8`, `utf16` or `codepoint`",
+			self.encoding
+		)
+	}
+}
+
+impl error::Error for ParseOffsetEncodingError {}
+
+impl str::FromStr for OffsetEncoding {
+	type Err = ParseOffsetEncodingError;
+
+	/// Parses `utf8`, `utf16` or `codepoint`, ignoring case and dashes, so
+	/// that `UTF-16` is accepted too.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.replace('-', "").to_ascii_lowercase().as_str() {
+			"utf8" => Ok(OffsetEncoding::Utf8),
+			"utf16" => Ok(OffsetEncoding::Utf16),
+			"codepoint" => Ok(OffsetEncoding::CodePoint),
+			_ => Err(ParseOffsetEncodingError {
+				encoding: String::from(s),
+			}),
+		}
+	}
+}
+
+/// Converts the byte offsets of a [parse_to_vec](crate::parse_to_vec) or
+/// [parse_to_vec_with_trivia](crate::parse_to_vec_with_trivia) array into
+/// offsets in the given encoding, in place.
+///
+/// The offsets are converted in a single pass over the contents, as the
+/// tokens are in order. An offset past the end of the contents, or within a
+/// character, is first moved back to the end or to the start of that
+/// character.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::OffsetEncoding;
+///
+/// let contents = "[é]\nkey = 😀\n";
+/// let mut tokens = one_ini::parse_to_vec(contents).unwrap();
+/// one_ini::encode_offsets(contents, &mut tokens, OffsetEncoding::Utf16);
+///
+/// assert_eq!(tokens, [2, 1, 2, 0, 4, 7, 1, 10, 12]);
+/// ```
+pub fn encode_offsets(contents: &str, tokens: &mut [u32], encoding: OffsetEncoding) {
+	// The byte offset reached so far, and the same offset in the encoding.
+	let (mut byte, mut encoded) = (0, 0);
+	for token in tokens.chunks_mut(3) {
+		for offset in &mut token[1..] {
+			let target = clamp(contents, *offset as usize);
+			if target < byte {
+				(byte, encoded) = (0, 0);
+			}
+			encoded += encoding.offset(&contents[byte..], target - byte);
+			byte = target;
+			*offset = encoded as u32;
+		}
+	}
+}
@@ -0,0 +1,138 @@
+//! # Parse errors
+//!
+//! A stable error type for contents that do not conform to the
+//! [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+//! file format, independent of the grammar used to parse them.
+
+use crate::LineIndex;
+use crate::Rule;
+use pest::error::InputLocation;
+use serde::{Deserialize, Serialize};
+use std::{error, fmt, str};
+
+/// What made the contents invalid.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+	/// A line starts with `[` but does not end with `]`.
+	UnterminatedSectionHeader,
+	/// A line that looks like a pair starts with `[`, which may only begin a
+	/// section header.
+	KeyStartsWithBracket,
+	/// A line is neither blank, a comment, a pair nor a section header.
+	MissingEquals,
+	/// A line starts with `=`, so its pair has no key.
+	EmptyKey,
+	/// The contents are not valid UTF-8.
+	InvalidUtf8,
+}
+
+impl ParseErrorKind {
+	pub(crate) fn message(&self) -> &'static str {
+		match self {
+			ParseErrorKind::UnterminatedSectionHeader => "section header must end with `]`",
+			ParseErrorKind::KeyStartsWithBracket => "key must not start with `[`",
+			ParseErrorKind::MissingEquals => "expected `=` after key",
+			ParseErrorKind::EmptyKey => "expected key before `=`",
+			ParseErrorKind::InvalidUtf8 => "invalid UTF-8",
+		}
+	}
+}
+
+/// An error raised for contents that cannot be parsed, pointing at the start
+/// of the offending line.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::ParseErrorKind;
+///
+/// let error = one_ini::parse("root=true\n  [*.md\n").unwrap_err();
+///
+/// assert_eq!(error.kind, ParseErrorKind::UnterminatedSectionHeader);
+/// assert_eq!((error.offset, error.line, error.column), (12, 2, 3));
+/// assert_eq!(error.to_string(), "section header must end with `]` at line 2, column 3");
+/// ```
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+	pub kind: ParseErrorKind,
+	/// The UTF-8 byte offset of the error.
+	pub offset: usize,
+	/// The one-based line number of the error.
+	pub line: usize,
+	/// The one-based column of the error, counted in characters.
+	pub column: usize,
+	/// A human-readable description of the error, without its location.
+	pub message: String,
+}
+
+impl ParseError {
+	pub(crate) fn new(kind: ParseErrorKind, lines: &LineIndex, offset: usize) -> Self {
+		let position = lines.position(offset);
+		ParseError {
+			kind,
+			offset,
+			line: position.line,
+			column: position.column,
+			message: String::from(kind.message()),
+		}
+	}
+
+	/// Classifies the line a grammar error was raised on.
+	pub(crate) fn from_pest(error: pest::error::Error<Rule>, contents: &str) -> Self {
+		let offset = match error.location {
+			InputLocation::Pos(pos) => pos,
+			InputLocation::Span((start, _)) => start,
+		};
+		ParseError::invalid_line(contents, &LineIndex::new(contents), offset)
+	}
+
+	/// Classifies an invalid line, given any offset within it, and points at
+	/// its first character after leading whitespace. The lines are those of
+	/// the contents, so that they are only indexed once per parse.
+	pub(crate) fn invalid_line(contents: &str, lines: &LineIndex, offset: usize) -> Self {
+		let line_start = contents[..offset].rfind(['\r', '\n']).map_or(0, |i| i + 1);
+		let line_end = contents[offset..]
+			.find(['\r', '\n'])
+			.map_or(contents.len(), |i| offset + i);
+		let line = &contents[line_start..line_end];
+		// Only the first line may start with a byte order mark.
+		let text = match line_start {
+			0 => line.strip_prefix('\u{feff}').unwrap_or(line),
+			_ => line,
+		}
+		.trim_start_matches([' ', '\t']);
+		let kind = if text.starts_with('[') {
+			if text.contains('=') {
+				ParseErrorKind::KeyStartsWithBracket
+			} else {
+				ParseErrorKind::UnterminatedSectionHeader
+			}
+		} else if text.starts_with('=') {
+			ParseErrorKind::EmptyKey
+		} else {
+			ParseErrorKind::MissingEquals
+		};
+		ParseError::new(kind, lines, line_start + line.len() - text.len())
+	}
+
+	/// Reports the first invalid byte of contents that are not UTF-8.
+	pub(crate) fn from_utf8(error: str::Utf8Error, contents: &[u8]) -> Self {
+		let valid = error.valid_up_to();
+		// The prefix was just validated, so this cannot fail.
+		let prefix = str::from_utf8(&contents[..valid]).unwrap();
+		ParseError::new(ParseErrorKind::InvalidUtf8, &LineIndex::new(prefix), valid)
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			formatter,
+			"{} at line {}, column {}",
+			self.message, self.line, self.column
+		)
+	}
+}
+
+impl error::Error for ParseError {}
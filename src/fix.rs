@@ -0,0 +1,156 @@
+//! # Fixing
+//!
+//! Rewrites the contents of a file so that it follows the
+//! [properties](crate::Properties) that apply to it, fixing the
+//! [violations](crate::check) that can be fixed mechanically.
+
+use crate::check::{lines, tab_stop, BOM};
+use crate::{Charset, EndOfLine, IndentStyle, Properties};
+
+/// Rewrites contents to follow the properties that apply to them.
+///
+/// - Every line ending is converted to the `end_of_line`.
+/// - Trailing spaces and tabs are removed where `trim_trailing_whitespace`
+///   is `true`.
+/// - A final newline is added where `insert_final_newline` is `true`, and
+///   every trailing line ending is removed where it is `false`.
+/// - Indentation is converted to the `indent_style`. Tabs and spaces count
+///   up to the next multiple of the `tab_width` for spaces, and of the
+///   `indent_size` for tabs, and any indentation left over after the last
+///   tab is kept as spaces, for alignment.
+/// - A byte order mark is added where the `charset` is `utf-8-bom`, and
+///   removed where it is `utf-8` or `latin1`.
+///
+/// Lines longer than the `max_line_length` and invalid UTF-8 are left as
+/// they are, as are files whose `charset` is UTF-16. Properties that are not
+/// set are not fixed.
+///
+/// # Example
+///
+/// ```
+/// let mut properties = one_ini::Properties::new();
+/// properties.insert("indent_style", "space");
+/// properties.insert("indent_size", "2");
+/// properties.insert("end_of_line", "lf");
+/// properties.insert("trim_trailing_whitespace", "true");
+/// properties.insert("insert_final_newline", "true");
+///
+/// assert_eq!(one_ini::fix(b"a {\r\n\tb; \r\n}", &properties), b"a {\n  b;\n}\n");
+/// ```
+pub fn fix(contents: &[u8], properties: &Properties) -> Vec<u8> {
+	let charset = properties.charset();
+	if let Some(Charset::Utf16Be | Charset::Utf16Le) = charset {
+		return contents.to_vec();
+	}
+	let lines = lines(contents);
+	let end_of_line = properties
+		.end_of_line()
+		.map(|end_of_line| match end_of_line {
+			EndOfLine::Lf => &b"\n"[..],
+			EndOfLine::Cr => b"\r",
+			EndOfLine::Crlf => b"\r\n",
+		});
+	let indent = Indent::new(properties);
+	let trim_trailing_whitespace = properties.trim_trailing_whitespace() == Some(true);
+	let mut fixed = Vec::with_capacity(contents.len() + BOM.len());
+	let has_bom = contents.starts_with(BOM);
+	let bom = match charset {
+		Some(Charset::Utf8 | Charset::Latin1) => false,
+		Some(Charset::Utf8Bom) => true,
+		_ => has_bom,
+	};
+	if bom {
+		fixed.extend_from_slice(BOM);
+	}
+	for &(start, end, next) in &lines {
+		let mut line = &contents[start..end];
+		if trim_trailing_whitespace {
+			let content_end = line
+				.iter()
+				.rposition(|&b| b != b' ' && b != b'\t')
+				.map_or(0, |i| i + 1);
+			line = &line[..content_end];
+		}
+		if let Some(indent) = &indent {
+			line = indent.write(&mut fixed, line);
+		}
+		fixed.extend_from_slice(line);
+		if end < next {
+			fixed.extend_from_slice(end_of_line.unwrap_or(&contents[end..next]));
+		}
+	}
+	match properties.insert_final_newline() {
+		Some(true) => {
+			if let Some(&(_, end, next)) = lines.last() {
+				if end == next {
+					// Without an `end_of_line`, follow the file's first line
+					// ending.
+					let first = lines.first().map(|&(_, end, next)| &contents[end..next]);
+					let end_of_line = end_of_line
+						.or(first.filter(|ending| !ending.is_empty()))
+						.unwrap_or(b"\n");
+					fixed.extend_from_slice(end_of_line);
+				}
+			}
+		}
+		Some(false) => {
+			let content_end = fixed
+				.iter()
+				.rposition(|&b| b != b'\r' && b != b'\n')
+				.map_or(0, |i| i + 1)
+				.max(if bom { BOM.len() } else { 0 });
+			fixed.truncate(content_end);
+		}
+		None => (),
+	}
+	fixed
+}
+
+/// How to re-indent lines.
+struct Indent {
+	style: IndentStyle,
+	/// The number of columns a tab advances to a multiple of.
+	tab_stop: usize,
+}
+
+impl Indent {
+	/// The indentation to convert to, if the `indent_style` and the size of a
+	/// tab are known.
+	fn new(properties: &Properties) -> Option<Self> {
+		let style = properties.indent_style()?;
+		let tab_stop = match style {
+			IndentStyle::Space => match properties.tab_width()? {
+				0 => return None,
+				tab_width => tab_width as usize,
+			},
+			IndentStyle::Tab => tab_stop(properties)?,
+		};
+		Some(Indent { style, tab_stop })
+	}
+
+	/// Writes the indentation of a line converted to the style, returning the
+	/// rest of the line. Whitespace-only lines are left as they are.
+	fn write<'a>(&self, out: &mut Vec<u8>, line: &'a [u8]) -> &'a [u8] {
+		let indent = line
+			.iter()
+			.take_while(|&&b| b == b' ' || b == b'\t')
+			.count();
+		if indent == line.len() {
+			return line;
+		}
+		let mut width = 0;
+		for &b in &line[..indent] {
+			width = match b {
+				b'\t' => (width / self.tab_stop + 1) * self.tab_stop,
+				_ => width + 1,
+			};
+		}
+		let (tabs, spaces) = match self.style {
+			IndentStyle::Space => (0, width),
+			IndentStyle::Tab => (width / self.tab_stop, width % self.tab_stop),
+		};
+		out.extend(std::iter::repeat_n(b'\t', tabs));
+		out.extend(std::iter::repeat_n(b' ', spaces));
+		&line[indent..]
+	}
+}
@@ -0,0 +1,240 @@
+//! # Formatting
+//!
+//! Prints an [AST](crate::OneINIAST) in a canonical style. The
+//! [`Display`](std::fmt::Display) implementations of the AST nodes use the
+//! [default options](FormatOptions::default).
+
+use crate::{parse_recovering, Comment, EndOfLine, Invalid, Item, OneINIAST, Pair, Section};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+/// How [format()] prints a file.
+///
+/// # Example
+///
+/// ```
+/// let options = one_ini::FormatOptions {
+///     spaces_around_equals: true,
+///     align_equals: true,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     one_ini::format("[*]\nindent_style=tab\ncharset=utf-8\n", options),
+///     "[*]\nindent_style = tab\ncharset      = utf-8\n",
+/// );
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormatOptions {
+	/// Surrounds `=` with a space on each side.
+	pub spaces_around_equals: bool,
+	/// Lowercases every key. Values are left as written.
+	pub lowercase_keys: bool,
+	/// The number of blank lines before each section, other than one at the
+	/// start of the file.
+	pub blank_lines_between_sections: usize,
+	/// Replaces the indicator of every comment. `None` keeps each comment's
+	/// own indicator.
+	pub comment_indicator: Option<CommentIndicator>,
+	/// Ends every line with `\n`, `\r\n` or `\r`.
+	pub end_of_line: EndOfLine,
+	/// Pads the keys of the pairs in each section, and in the prelude, so that
+	/// their `=` line up.
+	pub align_equals: bool,
+}
+
+/// The character that starts a comment line.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommentIndicator {
+	/// `#`
+	#[serde(rename = "#")]
+	Hash,
+	/// `;`
+	#[serde(rename = ";")]
+	Semicolon,
+}
+
+impl CommentIndicator {
+	/// The indicator as a character.
+	pub fn as_char(self) -> char {
+		match self {
+			CommentIndicator::Hash => '#',
+			CommentIndicator::Semicolon => ';',
+		}
+	}
+}
+
+impl Default for FormatOptions {
+	/// The style of the [`Display`](std::fmt::Display) implementations: no
+	/// spaces around `=`, keys as written, one blank line between sections,
+	/// comment indicators as written, and `\n` line endings.
+	fn default() -> Self {
+		FormatOptions {
+			spaces_around_equals: false,
+			lowercase_keys: false,
+			blank_lines_between_sections: 1,
+			comment_indicator: None,
+			end_of_line: EndOfLine::Lf,
+			align_equals: false,
+		}
+	}
+}
+
+/// Formats [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+/// contents.
+///
+/// Blank lines and the whitespace around keys and values are dropped. Lines
+/// that cannot be parsed are kept as they are, without surrounding
+/// whitespace, so that formatting never loses any content. A leading byte
+/// order mark is kept.
+///
+/// # Example
+///
+/// ```
+/// let contents = "root = true\n\n\n[*]\r\n  INDENT_STYLE =   tab\n; done\n";
+/// let options = one_ini::FormatOptions {
+///     lowercase_keys: true,
+///     comment_indicator: Some(one_ini::CommentIndicator::Hash),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     one_ini::format(contents, options),
+///     "root=true\n\n[*]\nindent_style=tab\n# done\n",
+/// );
+/// ```
+pub fn format(contents: &str, options: FormatOptions) -> String {
+	let (ast, _) = parse_recovering(contents);
+	let mut formatted = String::with_capacity(contents.len());
+	if contents.starts_with('\u{feff}') {
+		formatted.push('\u{feff}');
+	}
+	// Writing to a `String` cannot fail.
+	let _ = Printer::new(&options).ast(&mut formatted, &ast);
+	formatted
+}
+
+impl OneINIAST {
+	/// Prints the AST with the given options.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let ast = one_ini::parse("[a]\nb=c\n[d]\n").unwrap();
+	/// let options = one_ini::FormatOptions {
+	///     blank_lines_between_sections: 0,
+	///     end_of_line: one_ini::EndOfLine::Crlf,
+	///     ..Default::default()
+	/// };
+	///
+	/// assert_eq!(ast.to_string_with(&options), "[a]\r\nb=c\r\n[d]\r\n");
+	/// ```
+	pub fn to_string_with(&self, options: &FormatOptions) -> String {
+		let mut formatted = String::new();
+		let _ = Printer::new(options).ast(&mut formatted, self);
+		formatted
+	}
+}
+
+/// Writes AST nodes according to a set of options.
+pub(crate) struct Printer<'a> {
+	options: &'a FormatOptions,
+	end_of_line: &'static str,
+}
+
+impl<'a> Printer<'a> {
+	pub(crate) fn new(options: &'a FormatOptions) -> Self {
+		let end_of_line = match options.end_of_line {
+			EndOfLine::Lf => "\n",
+			EndOfLine::Cr => "\r",
+			EndOfLine::Crlf => "\r\n",
+		};
+		Printer {
+			options,
+			end_of_line,
+		}
+	}
+
+	pub(crate) fn ast(&self, out: &mut dyn Write, ast: &OneINIAST) -> fmt::Result {
+		self.items(out, &ast.body)
+	}
+
+	/// Writes the items of a prelude or section body.
+	pub(crate) fn items(&self, out: &mut dyn Write, items: &[Item]) -> fmt::Result {
+		let key_width = match self.options.align_equals {
+			true => items
+				.iter()
+				.filter_map(|item| match item {
+					Item::Pair(pair) => Some(self.key(pair).chars().count()),
+					_ => None,
+				})
+				.max()
+				.unwrap_or(0),
+			false => 0,
+		};
+		for (i, item) in items.iter().enumerate() {
+			if let Item::Section(_) = item {
+				if i > 0 {
+					for _ in 0..self.options.blank_lines_between_sections {
+						out.write_str(self.end_of_line)?;
+					}
+				}
+			}
+			self.item(out, item, key_width)?;
+		}
+		Ok(())
+	}
+
+	/// Writes a single item, padding the key of a pair to `key_width`
+	/// characters.
+	pub(crate) fn item(&self, out: &mut dyn Write, item: &Item, key_width: usize) -> fmt::Result {
+		match item {
+			Item::Comment(comment) => self.comment(out, comment),
+			Item::Pair(pair) => self.pair(out, pair, key_width),
+			Item::Section(section) => self.section(out, section),
+			Item::Error(invalid) => self.invalid(out, invalid),
+		}
+	}
+
+	pub(crate) fn invalid(&self, out: &mut dyn Write, invalid: &Invalid) -> fmt::Result {
+		out.write_str(&invalid.text)?;
+		out.write_str(self.end_of_line)
+	}
+
+	pub(crate) fn comment(&self, out: &mut dyn Write, comment: &Comment) -> fmt::Result {
+		let indicator = match self.options.comment_indicator {
+			Some(indicator) => indicator.as_char(),
+			None => comment.indicator,
+		};
+		write!(out, "{}{}{}", indicator, comment.value, self.end_of_line)
+	}
+
+	pub(crate) fn pair(&self, out: &mut dyn Write, pair: &Pair, key_width: usize) -> fmt::Result {
+		let key = self.key(pair);
+		out.write_str(&key)?;
+		for _ in key.chars().count()..key_width {
+			out.write_char(' ')?;
+		}
+		match (self.options.spaces_around_equals, pair.value.is_empty()) {
+			(true, true) => out.write_str(" =")?,
+			(true, false) => out.write_str(" = ")?,
+			(false, _) => out.write_char('=')?,
+		}
+		out.write_str(&pair.value)?;
+		out.write_str(self.end_of_line)
+	}
+
+	fn key<'p>(&self, pair: &'p Pair) -> Cow<'p, str> {
+		match self.options.lowercase_keys {
+			true => Cow::Owned(pair.key.to_lowercase()),
+			false => Cow::Borrowed(&pair.key),
+		}
+	}
+
+	pub(crate) fn section(&self, out: &mut dyn Write, section: &Section) -> fmt::Result {
+		write!(out, "[{}]{}", section.name, self.end_of_line)?;
+		self.items(out, &section.body)
+	}
+}
@@ -0,0 +1,340 @@
+//! # Glob matching
+//!
+//! Compiles [section](crate::Section) names into matchers following the
+//! [EditorConfig glob expressions](https://spec.editorconfig.org/#glob-expressions).
+
+/// A compiled section name.
+///
+/// | Pattern        | Matches                                                   |
+/// | -------------- | --------------------------------------------------------- |
+/// | `*`            | any string of characters, except path separators (`/`)   |
+/// | `**`           | any string of characters                                  |
+/// | `?`            | any single character, except path separators (`/`)       |
+/// | `[name]`       | any single character in _name_                            |
+/// | `[!name]`      | any single character not in _name_                        |
+/// | `{s1,s2,s3}`   | any of the strings given (separated by commas)            |
+/// | `{num1..num2}` | any integer numbers between _num1_ and _num2_             |
+/// | `\`            | escapes the following character                           |
+///
+/// A pattern containing a `/` is matched against the whole path relative to
+/// the directory of the `.editorconfig` file, ignoring a leading `/`. Any
+/// other pattern may match the trailing components of a path at any depth,
+/// as if it were prefixed by `**/`.
+///
+/// Characters that cannot form a valid expression, such as an unmatched `[`
+/// or `{`, or a `{single}` alternative without any commas, match themselves.
+///
+/// # Example
+///
+/// ```
+/// let glob = one_ini::Glob::new("*.{js,ts}");
+///
+/// assert!(glob.is_match("index.js"));
+/// assert!(glob.is_match("src/lib/util.ts"));
+/// assert!(!glob.is_match("index.jsx"));
+///
+/// let glob = one_ini::Glob::new("/lib/**.rs");
+///
+/// assert!(glob.is_match("lib/a/b.rs"));
+/// assert!(!glob.is_match("src/lib/a.rs"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Glob {
+	pattern: String,
+	tokens: Vec<Token>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Char(char),
+	/// `?`
+	AnyChar,
+	/// `*`
+	Star,
+	/// `**`
+	DoubleStar,
+	/// `/**/`, which also matches a single `/`.
+	AnyDirs,
+	/// `[...]` or `[!...]`, as inclusive character ranges.
+	Class {
+		negated: bool,
+		ranges: Vec<(char, char)>,
+	},
+	/// `{s1,s2,s3}`, as the indices of the first token of each alternative.
+	Alternatives(Vec<usize>),
+	/// The end of an alternative, continuing at the index after the braces.
+	Jump(usize),
+	/// `{num1..num2}`
+	Range(i64, i64),
+}
+
+impl Glob {
+	pub fn new(pattern: &str) -> Self {
+		let anchored = if pattern.starts_with('/') {
+			pattern.to_string()
+		} else if pattern.contains('/') {
+			format!("/{}", pattern)
+		} else {
+			format!("/**/{}", pattern)
+		};
+		let chars: Vec<char> = anchored.chars().collect();
+		let mut tokens = vec![];
+		compile(&chars, &mut tokens);
+		Glob {
+			pattern: pattern.to_string(),
+			tokens,
+		}
+	}
+
+	/// The section name this glob was compiled from.
+	pub fn as_str(&self) -> &str {
+		&self.pattern
+	}
+
+	/// Tests a `/`-separated path, relative to the directory of the
+	/// `.editorconfig` file.
+	pub fn is_match(&self, path: &str) -> bool {
+		let text: Vec<char> = std::iter::once('/')
+			.chain(path.trim_start_matches('/').chars())
+			.collect();
+		Matcher::new(&self.tokens, &text).matches(0, 0)
+	}
+}
+
+/// Compiles a pattern, appending its tokens to `tokens`. Brace expressions
+/// are laid out in line, each alternative followed by a jump past the
+/// others, so that a position in the pattern is a single index.
+fn compile(pattern: &[char], tokens: &mut Vec<Token>) {
+	let mut i = 0;
+	while i < pattern.len() {
+		match pattern[i] {
+			'\\' if i + 1 < pattern.len() => {
+				tokens.push(Token::Char(pattern[i + 1]));
+				i += 2;
+			}
+			'/' if pattern[i + 1..].starts_with(&['*', '*', '/']) => {
+				tokens.push(Token::AnyDirs);
+				i += 4;
+			}
+			'*' if pattern.get(i + 1) == Some(&'*') => {
+				tokens.push(Token::DoubleStar);
+				i += 2;
+			}
+			'*' => {
+				tokens.push(Token::Star);
+				i += 1;
+			}
+			'?' => {
+				tokens.push(Token::AnyChar);
+				i += 1;
+			}
+			'[' => match compile_class(&pattern[i..]) {
+				Some((token, len)) => {
+					tokens.push(token);
+					i += len;
+				}
+				None => {
+					tokens.push(Token::Char('['));
+					i += 1;
+				}
+			},
+			'{' => match compile_braces(&pattern[i..], tokens) {
+				Some(len) => i += len,
+				None => {
+					tokens.push(Token::Char('{'));
+					i += 1;
+				}
+			},
+			c => {
+				tokens.push(Token::Char(c));
+				i += 1;
+			}
+		}
+	}
+}
+
+/// Compiles the bracket expression at the start of `pattern`, returning the
+/// token and the number of characters consumed, or `None` if the bracket
+/// is not closed or contains a path separator.
+fn compile_class(pattern: &[char]) -> Option<(Token, usize)> {
+	let mut i = 1;
+	let negated = pattern.get(i) == Some(&'!');
+	if negated {
+		i += 1;
+	}
+	let mut members = vec![];
+	let start = i;
+	loop {
+		let c = match pattern.get(i) {
+			None | Some('/') => return None,
+			Some(']') if i > start => break,
+			Some('\\') if i + 1 < pattern.len() => {
+				i += 1;
+				pattern[i]
+			}
+			Some(&c) => c,
+		};
+		members.push(c);
+		i += 1;
+	}
+	let mut ranges = vec![];
+	let mut j = 0;
+	while j < members.len() {
+		if j + 2 < members.len() && members[j + 1] == '-' {
+			ranges.push((members[j], members[j + 2]));
+			j += 3;
+		} else {
+			ranges.push((members[j], members[j]));
+			j += 1;
+		}
+	}
+	Some((Token::Class { negated, ranges }, i + 1))
+}
+
+/// Compiles the brace expression at the start of `pattern`, appending its
+/// tokens to `tokens` and returning the number of characters consumed, or
+/// `None` if the brace is never closed.
+fn compile_braces(pattern: &[char], tokens: &mut Vec<Token>) -> Option<usize> {
+	let mut depth = 0;
+	let mut commas = vec![];
+	let mut i = 1;
+	let end = loop {
+		match pattern.get(i)? {
+			'\\' => i += 1,
+			'{' => depth += 1,
+			'}' if depth == 0 => break i,
+			'}' => depth -= 1,
+			',' if depth == 0 => commas.push(i),
+			_ => (),
+		}
+		i += 1;
+	};
+	let inner = &pattern[1..end];
+	if commas.is_empty() {
+		match parse_range(inner) {
+			Some(range) => tokens.push(range),
+			None => {
+				tokens.push(Token::Char('{'));
+				compile(inner, tokens);
+				tokens.push(Token::Char('}'));
+			}
+		}
+		return Some(end + 1);
+	}
+	let alternatives = tokens.len();
+	tokens.push(Token::Alternatives(vec![]));
+	let mut starts = vec![];
+	let mut jumps = vec![];
+	let mut start = 1;
+	for comma in commas.into_iter().chain(std::iter::once(end)) {
+		starts.push(tokens.len());
+		compile(&pattern[start..comma], tokens);
+		jumps.push(tokens.len());
+		tokens.push(Token::Jump(0));
+		start = comma + 1;
+	}
+	for jump in jumps {
+		tokens[jump] = Token::Jump(tokens.len());
+	}
+	tokens[alternatives] = Token::Alternatives(starts);
+	Some(end + 1)
+}
+
+/// Parses `num1..num2` into a range token.
+fn parse_range(inner: &[char]) -> Option<Token> {
+	let inner: String = inner.iter().collect();
+	let (from, to) = inner.split_once("..")?;
+	let valid = |s: &str| {
+		let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+		!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+	};
+	if !valid(from) || !valid(to) {
+		return None;
+	}
+	let (from, to): (i64, i64) = (from.parse().ok()?, to.parse().ok()?);
+	Some(Token::Range(from.min(to), from.max(to)))
+}
+
+/// Matches text against compiled tokens, remembering the states that failed
+/// so that each token is tried at each position at most once, however many
+/// stars the pattern has.
+struct Matcher<'a> {
+	tokens: &'a [Token],
+	text: &'a [char],
+	/// Whether the tokens from an index fail to match the text from an
+	/// index, by `token * (text.len() + 1) + position`.
+	failed: Vec<bool>,
+}
+
+impl<'a> Matcher<'a> {
+	fn new(tokens: &'a [Token], text: &'a [char]) -> Self {
+		Matcher {
+			tokens,
+			text,
+			failed: vec![false; tokens.len() * (text.len() + 1)],
+		}
+	}
+
+	/// Whether the tokens from `token` on match the text from `position` on.
+	fn matches(&mut self, token: usize, position: usize) -> bool {
+		let Some(current) = self.tokens.get(token) else {
+			return position == self.text.len();
+		};
+		let state = token * (self.text.len() + 1) + position;
+		if self.failed[state] {
+			return false;
+		}
+		let text = &self.text[position..];
+		let next = token + 1;
+		let matched = match current {
+			Token::Char(c) => text.first() == Some(c) && self.matches(next, position + 1),
+			Token::AnyChar => match text.first() {
+				Some('/') | None => false,
+				Some(_) => self.matches(next, position + 1),
+			},
+			Token::Star => {
+				let end = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+				(0..=end).any(|i| self.matches(next, position + i))
+			}
+			Token::DoubleStar => (0..=text.len()).any(|i| self.matches(next, position + i)),
+			Token::AnyDirs => {
+				text.first() == Some(&'/')
+					&& (0..text.len())
+						.filter(|&i| text[i] == '/')
+						.any(|i| self.matches(next, position + i + 1))
+			}
+			Token::Class { negated, ranges } => match text.first() {
+				Some('/') | None => false,
+				Some(&c) => {
+					let contained = ranges.iter().any(|&(from, to)| from <= c && c <= to);
+					contained != *negated && self.matches(next, position + 1)
+				}
+			},
+			Token::Alternatives(starts) => {
+				starts.iter().any(|&start| self.matches(start, position))
+			}
+			Token::Jump(to) => self.matches(*to, position),
+			Token::Range(from, to) => {
+				let sign = match text.first() {
+					Some('+') | Some('-') => 1,
+					_ => 0,
+				};
+				let digits = text[sign..]
+					.iter()
+					.take_while(|c| c.is_ascii_digit())
+					.count();
+				(sign + 1..=sign + digits).rev().any(|end| {
+					let number: String = text[..end].iter().collect();
+					match number.parse::<i64>() {
+						Ok(n) => *from <= n && n <= *to && self.matches(next, position + end),
+						Err(_) => false,
+					}
+				})
+			}
+		};
+		if !matched {
+			self.failed[state] = true;
+		}
+		matched
+	}
+}
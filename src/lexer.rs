@@ -0,0 +1,418 @@
+//! # Lexer
+//!
+//! A hand-written scanner for the grammar in `ini.pest`, which
+//! [parse_to_vec](crate::parse_to_vec), [parse](crate::parse) and the other
+//! hot paths use instead of the generated parser. It walks the bytes of the
+//! contents once, without allocating, and yields a [Lexeme] for each line
+//! that is not blank.
+//!
+//! Every rule of the grammar is confined to a single line, so each line is
+//! classified on its own:
+//!
+//! - A line whose first character, after leading whitespace, is `[` is a
+//!   section header if it ends with `]` and trailing whitespace, with at
+//!   least one character between the brackets.
+//! - A line that starts with `#` or `;` is a comment, even if it contains
+//!   `=`, so that commented-out properties stay comments.
+//! - Any other line that contains `=` is a pair, unless it starts with `=`.
+//! - Anything else is invalid.
+
+use crate::{INIParser, ParseError, Rule, TokenTypes};
+use pest::iterators::Pair;
+use pest::Parser;
+use std::ops::Range;
+
+const BOM: &[u8] = "\u{feff}".as_bytes();
+
+/// A line that is not blank, with the byte ranges of its tokens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Lexeme {
+	/// A section header, with the range of the name between its brackets.
+	Section { name: Range<usize> },
+	/// A pair, with its key and value trimmed of surrounding whitespace.
+	Pair {
+		key: Range<usize>,
+		value: Range<usize>,
+	},
+	/// A comment, with the offset of its indicator and the range of the
+	/// rest of the line.
+	Comment {
+		indicator: usize,
+		value: Range<usize>,
+	},
+	/// A line the grammar rejects, trimmed of surrounding whitespace.
+	Invalid { text: Range<usize> },
+}
+
+/// The lines of some contents, in order. Blank lines are skipped.
+pub(crate) struct Lexer<'a> {
+	bytes: &'a [u8],
+	/// The start of the next line, past the end of the contents when every
+	/// line has been scanned.
+	offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+	pub(crate) fn new(contents: &'a str) -> Self {
+		let bytes = contents.as_bytes();
+		// The grammar allows whitespace before the byte order mark.
+		let start = skip_whitespace(bytes, 0);
+		let offset = match bytes[start..].starts_with(BOM) {
+			true => start + BOM.len(),
+			false => 0,
+		};
+		Lexer { bytes, offset }
+	}
+
+	/// Classifies the line between `start` and `end`, without its line
+	/// break, or returns `None` for a blank line.
+	fn line(&self, start: usize, end: usize) -> Option<Lexeme> {
+		let bytes = self.bytes;
+		let start = skip_whitespace(bytes, start);
+		let trimmed_end = start + trim_whitespace_end(&bytes[start..end]);
+		match bytes[start..end].first()? {
+			b'[' => match trimmed_end - start > 2 && bytes[trimmed_end - 1] == b']' {
+				true => Some(Lexeme::Section {
+					name: start + 1..trimmed_end - 1,
+				}),
+				false => Some(Lexeme::Invalid {
+					text: start..trimmed_end,
+				}),
+			},
+			b'#' | b';' => Some(Lexeme::Comment {
+				indicator: start,
+				value: start + 1..end,
+			}),
+			b'=' => Some(Lexeme::Invalid {
+				text: start..trimmed_end,
+			}),
+			_ => match bytes[start..end].iter().position(|&b| b == b'=') {
+				Some(equals) => {
+					let equals = start + equals;
+					let key_end = start + trim_whitespace_end(&bytes[start..equals]);
+					let value_start = skip_whitespace(&bytes[..end], equals + 1);
+					Some(Lexeme::Pair {
+						key: start..key_end,
+						value: value_start..trimmed_end.max(value_start),
+					})
+				}
+				None => Some(Lexeme::Invalid {
+					text: start..trimmed_end,
+				}),
+			},
+		}
+	}
+}
+
+impl Iterator for Lexer<'_> {
+	type Item = Lexeme;
+
+	fn next(&mut self) -> Option<Lexeme> {
+		let bytes = self.bytes;
+		while self.offset <= bytes.len() {
+			let start = self.offset;
+			let end = bytes[start..]
+				.iter()
+				.position(|&b| b == b'\n' || b == b'\r')
+				.map_or(bytes.len(), |i| start + i);
+			self.offset = match &bytes[end..] {
+				[b'\r', b'\n', ..] => end + 2,
+				_ => end + 1,
+			};
+			if let Some(line) = self.line(start, end) {
+				return Some(line);
+			}
+		}
+		None
+	}
+}
+
+fn is_whitespace(b: &u8) -> bool {
+	*b == b' ' || *b == b'\t'
+}
+
+/// The offset of the first byte at or after `offset` that is not a space or
+/// a tab.
+fn skip_whitespace(bytes: &[u8], offset: usize) -> usize {
+	offset
+		+ bytes[offset..]
+			.iter()
+			.take_while(|b| is_whitespace(b))
+			.count()
+}
+
+/// The length of the bytes without their trailing spaces and tabs.
+fn trim_whitespace_end(bytes: &[u8]) -> usize {
+	bytes.len() - bytes.iter().rev().take_while(|b| is_whitespace(b)).count()
+}
+
+/// The [parse_to_vec](crate::parse_to_vec) token stream, built from the pest
+/// grammar instead of the lexer. It is the reference the lexer is tested and
+/// benchmarked against, and is not part of the public API.
+#[doc(hidden)]
+pub fn pest_to_vec(contents: &str) -> Result<Vec<u32>, ParseError> {
+	fn push(typ: TokenTypes, pair: Pair<'_, Rule>, results: &mut Vec<u32>) {
+		let span = pair.as_span();
+		results.extend([typ as u32, span.start() as u32, span.end() as u32]);
+	}
+
+	fn fill(pair: Pair<'_, Rule>, results: &mut Vec<u32>) {
+		for child in pair.into_inner() {
+			let rule = child.as_rule();
+			let mut inner = child.into_inner();
+			match rule {
+				Rule::section => {
+					let mut header = inner.next().unwrap().into_inner();
+					push(TokenTypes::Section, header.next().unwrap(), results);
+					fill(inner.next().unwrap(), results);
+				}
+				Rule::pair => {
+					push(TokenTypes::Key, inner.next().unwrap(), results);
+					push(TokenTypes::Value, inner.next().unwrap(), results);
+				}
+				Rule::comment => {
+					push(TokenTypes::CommentIndicator, inner.next().unwrap(), results);
+					push(TokenTypes::CommentValue, inner.next().unwrap(), results);
+				}
+				_ => (),
+			}
+		}
+	}
+
+	let mut parsed =
+		INIParser::parse(Rule::ini, contents).map_err(|e| ParseError::from_pest(e, contents))?;
+	let mut results = vec![];
+	fill(parsed.next().unwrap(), &mut results);
+	Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+	//! Differential tests of the lexer against the pest grammar, which stays
+	//! the reference for the syntax.
+
+	use crate::{
+		line_end, parse, parse_recovering, parse_to_vec, pest_to_vec, Comment, INIParser, Invalid,
+		Item, LineIndex, Pair, ParseError, Rule, Section,
+	};
+	use pest::iterators::Pair as PestPair;
+	use pest::Parser;
+	use std::fs;
+	use std::path::Path;
+
+	fn pest_body(pair: PestPair<'_, Rule>, lines: &LineIndex) -> Vec<Item> {
+		let mut body = vec![];
+		for p in pair.into_inner() {
+			let start = p.as_span().start();
+			let rule = p.as_rule();
+			let mut inner = p.into_inner();
+			body.push(match rule {
+				Rule::section => {
+					let header = inner.next().unwrap();
+					let header_end = header.as_span().end();
+					let name = header.into_inner().next().unwrap().as_span();
+					let body = pest_body(inner.next().unwrap(), lines);
+					let end = body
+						.last()
+						.and_then(Item::span)
+						.map_or(header_end, |span| span.end.offset);
+					Item::Section(Section {
+						name: String::from(name.as_str()),
+						body,
+						span: Some(lines.span(start, end)),
+						name_span: Some(lines.span(name.start(), name.end())),
+					})
+				}
+				Rule::pair => {
+					let key = inner.next().unwrap().as_span();
+					let value = inner.next().unwrap().as_span();
+					Item::Pair(Pair {
+						key: String::from(key.as_str()),
+						value: String::from(value.as_str()),
+						span: Some(lines.span(key.start(), value.end())),
+						key_span: Some(lines.span(key.start(), key.end())),
+						value_span: Some(lines.span(value.start(), value.end())),
+					})
+				}
+				Rule::comment => {
+					let indicator = inner.next().unwrap().as_span();
+					let value = inner.next().unwrap().as_span();
+					Item::Comment(Comment {
+						indicator: indicator.as_str().chars().next().unwrap(),
+						value: String::from(value.as_str()),
+						span: Some(lines.span(indicator.start(), value.end())),
+					})
+				}
+				_ => continue,
+			});
+		}
+		body
+	}
+
+	/// Parses with pest, blanking each invalid line and parsing again.
+	fn pest_recovering(contents: &str) -> (Vec<Item>, Vec<ParseError>) {
+		fn insert(body: &mut Vec<Item>, invalid: Invalid) {
+			let span = invalid.span.unwrap();
+			let index = body.partition_point(|item| {
+				item.span()
+					.is_none_or(|item| item.start.offset < span.start.offset)
+			});
+			if let Some(Item::Section(section)) = index.checked_sub(1).map(|i| &mut body[i]) {
+				let section_span = section.span.as_mut().unwrap();
+				if section_span.end.offset < span.end.offset {
+					section_span.end = span.end;
+				}
+				return insert(&mut section.body, invalid);
+			}
+			body.insert(index, Item::Error(invalid));
+		}
+
+		let lines = LineIndex::new(contents);
+		let mut masked = String::from(contents);
+		let mut errors = vec![];
+		let mut body = loop {
+			match INIParser::parse(Rule::ini, &masked) {
+				Ok(mut pairs) => break pest_body(pairs.next().unwrap(), &lines),
+				Err(e) => {
+					let error = ParseError::from_pest(e, &masked);
+					let end = line_end(&masked, error.offset);
+					masked.replace_range(error.offset..end, &" ".repeat(end - error.offset));
+					errors.push(error);
+				}
+			}
+		};
+		errors.sort_by_key(|error| error.offset);
+		for error in &errors {
+			let text = contents[error.offset..line_end(contents, error.offset)]
+				.trim_end_matches([' ', '\t']);
+			insert(
+				&mut body,
+				Invalid {
+					text: String::from(text),
+					kind: error.kind,
+					span: Some(lines.span(error.offset, error.offset + text.len())),
+				},
+			);
+		}
+		(body, errors)
+	}
+
+	fn assert_same(contents: &str) {
+		assert_eq!(
+			parse_to_vec(contents),
+			pest_to_vec(contents),
+			"{:?}",
+			contents
+		);
+		let lines = LineIndex::new(contents);
+		let expected = INIParser::parse(Rule::ini, contents)
+			.map(|mut pairs| pest_body(pairs.next().unwrap(), &lines))
+			.map_err(|e| ParseError::from_pest(e, contents));
+		assert_eq!(
+			format!("{:?}", parse(contents).map(|ast| ast.body)),
+			format!("{:?}", expected),
+			"{:?}",
+			contents
+		);
+		let (ast, errors) = parse_recovering(contents);
+		assert_eq!(
+			format!("{:?}", (ast.body, errors)),
+			format!("{:?}", pest_recovering(contents)),
+			"{:?}",
+			contents
+		);
+	}
+
+	const ALPHABET: [char; 12] = [
+		' ', '\t', '\n', '\r', '[', ']', '=', '#', ';', 'a', 'é', '\u{feff}',
+	];
+
+	#[test]
+	fn every_short_input() {
+		let mut inputs = vec![String::new()];
+		for _ in 0..4 {
+			inputs = inputs
+				.iter()
+				.flat_map(|input| {
+					ALPHABET.iter().map(move |&c| {
+						let mut input = input.clone();
+						input.push(c);
+						input
+					})
+				})
+				.collect();
+			for input in &inputs {
+				assert_same(input);
+			}
+		}
+	}
+
+	#[test]
+	fn random_inputs() {
+		// A xorshift generator, seeded for reproducible inputs.
+		let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+		let mut next = move || {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state as usize
+		};
+		for _ in 0..20_000 {
+			let len = next() % 40;
+			let input: String = (0..len)
+				.map(|_| ALPHABET[next() % ALPHABET.len()])
+				.collect();
+			assert_same(&input);
+		}
+	}
+
+	#[test]
+	fn line_shaped_inputs() {
+		let lines = [
+			"root = true",
+			"  [*.{md,txt}]  ",
+			"[]",
+			"[]]",
+			"[a] ]",
+			"[a]x",
+			"[a=b]",
+			"[a=b",
+			"# comment = pair",
+			"; comment",
+			"#",
+			"=value",
+			"  key\t=\t value with spaces \t",
+			"key =",
+			"no equals",
+			"\u{feff}[bom]",
+			" \u{feff}a=b",
+		];
+		for a in lines {
+			for b in lines {
+				for eol in ["\n", "\r\n", "\r", ""] {
+					assert_same(&format!("{}{}{}", a, eol, b));
+					assert_same(&format!("[s]{}{}{}{}{}", eol, a, eol, b, eol));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn fixtures() {
+		fn visit(dir: &Path) {
+			for entry in fs::read_dir(dir).unwrap() {
+				let path = entry.unwrap().path();
+				match path.is_dir() {
+					true => visit(&path),
+					false => {
+						if let Ok(contents) = fs::read_to_string(&path) {
+							assert_same(&contents);
+						}
+					}
+				}
+			}
+		}
+
+		visit(Path::new("tests/fixtures"));
+	}
+}
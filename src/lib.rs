@@ -16,21 +16,112 @@ extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
-use pest::error::Error;
-use pest::Parser;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::{env, fmt, str};
 use wasm_bindgen::prelude::*;
 
-#[derive(Parser)]
-#[grammar = "ini.pest"]
-struct INIParser;
+mod check;
+mod de;
+mod document;
+mod encoding;
+mod error;
+mod fix;
+mod format;
+mod glob;
+mod lexer;
+mod lint;
+mod lookup;
+mod property;
+mod resolve;
+mod ser;
+mod span;
+mod tokens;
+mod version;
 
+pub use check::{check, check_file, check_tree, Violation, ViolationCode};
+pub use de::{from_str, DeserializeError};
+pub use document::{
+	parse_document, Document, DocumentSection, Line, LineContent, SetPropertyError,
+};
+pub use encoding::{encode_offsets, OffsetEncoding, ParseOffsetEncodingError};
+pub use error::{ParseError, ParseErrorKind};
+pub use fix::fix;
+pub use format::{format, CommentIndicator, FormatOptions};
+pub use glob::Glob;
+pub use lint::{lint, Diagnostic, DiagnosticCode, Severity};
+pub use lookup::{find_configs, resolve_file, resolve_file_with, LookupError, DEFAULT_FILE_NAME};
+pub use property::{
+	Charset, EndOfLine, IndentSize, IndentStyle, MaxLineLength, Property, PropertyError, Setting,
+};
+pub use resolve::{resolve, resolve_for_version, Properties};
+pub use ser::{to_string, SerializeError};
+pub use span::{LineIndex, Position, Span};
+pub use tokens::{parse_to_vec_with_trivia, tokenize, Token};
+pub use version::{ParseVersionError, Version};
+
+#[doc(hidden)]
+pub use lexer::pest_to_vec;
+
+use format::Printer;
+
+mod grammar {
+	#[derive(Parser)]
+	#[grammar = "ini.pest"]
+	pub struct INIParser;
+}
+
+use grammar::{INIParser, Rule};
+use lexer::{Lexeme, Lexer};
+
+/// Parses contents into the JSON form of [OneINIAST].
+///
+/// Invalid contents throw a JS `Error` named `ParseError`, with the `kind`,
+/// `line`, `column` and `offset` of the [ParseError] as extra properties.
 #[wasm_bindgen]
-pub fn parse_to_json(contents: &str) -> JsValue {
-	let ast = parse(&contents).unwrap();
-	return serde_wasm_bindgen::to_value(&ast).unwrap();
-	//return JsValue::from_serde(&ast).unwrap();
+pub fn parse_to_json(contents: &str) -> Result<JsValue, js_sys::Error> {
+	let ast = parse(contents).map_err(to_js_error)?;
+	serde_wasm_bindgen::to_value(&ast).map_err(|e| js_sys::Error::new(&e.to_string()))
+}
+
+/// Copies a parse error onto a JS `Error`, which unlike [JsError] can carry
+/// properties other than its message.
+fn to_js_error(error: ParseError) -> js_sys::Error {
+	let js_error = js_sys::Error::new(&error.to_string());
+	js_error.set_name("ParseError");
+	let kind = serde_wasm_bindgen::to_value(&error.kind).unwrap_or(JsValue::UNDEFINED);
+	let properties = [
+		("kind", kind),
+		("line", JsValue::from(error.line)),
+		("column", JsValue::from(error.column)),
+		("offset", JsValue::from(error.offset)),
+	];
+	for (key, value) in properties {
+		// Setting a property on a fresh `Error` cannot fail.
+		let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str(key), &value);
+	}
+	js_error
+}
+
+/// Formats contents with options given as an object such as
+/// `{ spacesAroundEquals: true, endOfLine: "crlf" }`. Missing options take
+/// their [default](FormatOptions::default) values.
+#[wasm_bindgen(js_name = format)]
+pub fn format_with_options(contents: &str, options: JsValue) -> Result<String, JsError> {
+	let options = match options.is_undefined() || options.is_null() {
+		true => FormatOptions::default(),
+		false => serde_wasm_bindgen::from_value(options)?,
+	};
+	Ok(format(contents, options))
+}
+
+/// Fixes file contents to follow properties given as an object such as
+/// `{ end_of_line: "lf", insert_final_newline: "true" }`, the form of
+/// resolved properties.
+#[wasm_bindgen(js_name = fix)]
+pub fn fix_with_properties(contents: &[u8], properties: JsValue) -> Result<Vec<u8>, JsError> {
+	let properties: Properties = serde_wasm_bindgen::from_value(properties)?;
+	Ok(fix(contents, &properties))
 }
 
 #[wasm_bindgen]
@@ -39,7 +130,7 @@ pub fn version() -> String {
 }
 
 #[wasm_bindgen]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
 pub enum TokenTypes {
 	Key,
@@ -47,17 +138,102 @@ pub enum TokenTypes {
 	Section,
 	CommentIndicator,
 	CommentValue,
+	/// The `[` of a section header. This and the following types are never
+	/// produced by [parse_to_vec]: the trivia types come only from
+	/// [parse_to_vec_with_trivia], and `Invalid` also comes from [tokenize].
+	SectionOpen,
+	/// The `]` of a section header.
+	SectionClose,
+	/// The `=` of a pair.
+	Equals,
+	/// A run of spaces and tabs.
+	Whitespace,
+	/// A `\n`, `\r\n` or `\r` line break.
+	Newline,
+	/// A byte order mark at the start of the contents.
+	Bom,
+	/// A line that could not be parsed, without its surrounding whitespace.
+	Invalid,
+}
+
+/// Reads a token type back from the [parse_to_vec] or
+/// [parse_to_vec_with_trivia] stream.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::TokenTypes;
+///
+/// assert_eq!(TokenTypes::try_from(2), Ok(TokenTypes::Section));
+/// assert_eq!(TokenTypes::try_from(99), Err(99));
+/// ```
+impl TryFrom<u32> for TokenTypes {
+	type Error = u32;
+
+	fn try_from(value: u32) -> Result<Self, Self::Error> {
+		match value {
+			0 => Ok(TokenTypes::Key),
+			1 => Ok(TokenTypes::Value),
+			2 => Ok(TokenTypes::Section),
+			3 => Ok(TokenTypes::CommentIndicator),
+			4 => Ok(TokenTypes::CommentValue),
+			5 => Ok(TokenTypes::SectionOpen),
+			6 => Ok(TokenTypes::SectionClose),
+			7 => Ok(TokenTypes::Equals),
+			8 => Ok(TokenTypes::Whitespace),
+			9 => Ok(TokenTypes::Newline),
+			10 => Ok(TokenTypes::Bom),
+			11 => Ok(TokenTypes::Invalid),
+			_ => Err(value),
+		}
+	}
 }
 
 #[wasm_bindgen]
 pub fn parse_to_uint32array(contents: &[u8]) -> Result<Vec<u32>, JsError> {
-	let input = str::from_utf8(contents)?;
+	let input = str::from_utf8(contents).map_err(|e| ParseError::from_utf8(e, contents))?;
 	match parse_to_vec(input) {
 		Ok(res) => Ok(res),
 		Err(er) => Err(JsError::from(er)),
 	}
 }
 
+/// Parses a JS string like [parse_to_uint32array], with offsets in the given
+/// [encoding](OffsetEncoding): `"utf16"` to index the string itself,
+/// `"codepoint"` to index `Array.from(string)`, or `"utf8"` to index its
+/// UTF-8 bytes.
+#[wasm_bindgen]
+pub fn parse_string_to_uint32array(contents: &str, encoding: &str) -> Result<Vec<u32>, JsError> {
+	let encoding: OffsetEncoding = encoding.parse()?;
+	let mut tokens = parse_to_vec(contents)?;
+	encode_offsets(contents, &mut tokens, encoding);
+	Ok(tokens)
+}
+
+/// Tokenizes contents into an array of unsigned ints, like
+/// [parse_to_uint32array], that also has the trivia tokens of
+/// [parse_to_vec_with_trivia]. Invalid lines become `Invalid` tokens rather
+/// than errors.
+#[wasm_bindgen]
+pub fn parse_to_uint32array_with_trivia(contents: &[u8]) -> Result<Vec<u32>, JsError> {
+	let input = str::from_utf8(contents).map_err(|e| ParseError::from_utf8(e, contents))?;
+	Ok(parse_to_vec_with_trivia(input))
+}
+
+/// Tokenizes a JS string like [parse_to_uint32array_with_trivia], with
+/// offsets in the given [encoding](OffsetEncoding), as
+/// [parse_string_to_uint32array] does.
+#[wasm_bindgen]
+pub fn parse_string_to_uint32array_with_trivia(
+	contents: &str,
+	encoding: &str,
+) -> Result<Vec<u32>, JsError> {
+	let encoding: OffsetEncoding = encoding.parse()?;
+	let mut tokens = parse_to_vec_with_trivia(contents);
+	encode_offsets(contents, &mut tokens, encoding);
+	Ok(tokens)
+}
+
 /// Parses
 /// [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
 /// contents into an array of unsigned ints.  Each token consists of three
@@ -72,59 +248,34 @@ pub fn parse_to_uint32array(contents: &[u8]) -> Result<Vec<u32>, JsError> {
 /// let results = one_ini::parse_to_vec(&contents).unwrap();
 /// assert_eq!(results, vec![0, 0, 4, 1, 5, 9]);
 /// ```
-pub fn parse_to_vec(contents: &str) -> Result<Vec<u32>, Error<Rule>> {
-	let mut parsed = INIParser::parse(Rule::ini, contents)?;
+pub fn parse_to_vec(contents: &str) -> Result<Vec<u32>, ParseError> {
 	// 300 is slightly larger than the max size found in the test suite, and
 	// should be larger than most normal .editorconfig files, to avoid a few
 	// allocations.
 	let mut results = Vec::with_capacity(300);
-
-	fill_vec(parsed.next().unwrap(), &mut results);
-	Ok(results)
-}
-
-fn push_token(typ: TokenTypes, pair: pest::iterators::Pair<'_, Rule>, results: &mut Vec<u32>) {
-	let span = pair.as_span();
-	results.push(typ as u32);
-	results.push(span.start() as u32);
-	results.push(span.end() as u32);
-}
-
-fn fill_vec(pair: pest::iterators::Pair<'_, Rule>, results: &mut Vec<u32>) {
-	let children = pair.into_inner().filter(|p| match p.as_rule() {
-		Rule::EOI => false,
-		_ => true,
-	});
-	for child in children {
-		match child.as_rule() {
-			Rule::section => {
-				let mut inner_rules = child.into_inner();
-				let mut header = inner_rules.next().unwrap().into_inner();
-				push_token(TokenTypes::Section, header.next().unwrap(), results);
-				// Body always exists, even if empty
-				fill_vec(inner_rules.next().unwrap(), results);
+	let mut push = |typ: TokenTypes, span: Range<usize>| {
+		results.push(typ as u32);
+		results.push(span.start as u32);
+		results.push(span.end as u32);
+	};
+	for line in Lexer::new(contents) {
+		match line {
+			Lexeme::Section { name } => push(TokenTypes::Section, name),
+			Lexeme::Pair { key, value } => {
+				push(TokenTypes::Key, key);
+				push(TokenTypes::Value, value);
 			}
-			Rule::pair => {
-				let mut inner_rules = child.into_inner();
-				push_token(TokenTypes::Key, inner_rules.next().unwrap(), results);
-				push_token(TokenTypes::Value, inner_rules.next().unwrap(), results);
+			Lexeme::Comment { indicator, value } => {
+				push(TokenTypes::CommentIndicator, indicator..indicator + 1);
+				push(TokenTypes::CommentValue, value);
 			}
-			Rule::comment => {
-				let mut inner_rules = child.into_inner();
-				push_token(
-					TokenTypes::CommentIndicator,
-					inner_rules.next().unwrap(),
-					results,
-				);
-				push_token(
-					TokenTypes::CommentValue,
-					inner_rules.next().unwrap(),
-					results,
-				);
+			Lexeme::Invalid { text } => {
+				let lines = LineIndex::new(contents);
+				return Err(ParseError::invalid_line(contents, &lines, text.start));
 			}
-			_ => unreachable!(),
 		}
 	}
+	Ok(results)
 }
 
 /// Parses [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
@@ -138,49 +289,111 @@ fn fill_vec(pair: pest::iterators::Pair<'_, Rule>, results: &mut Vec<u32>) {
 ///
 /// assert_eq!(ast.to_string(), contents);
 /// ```
-pub fn parse(contents: &str) -> Result<OneINIAST, Error<Rule>> {
-	return match INIParser::parse(Rule::ini, contents) {
-		Ok(mut pairs) => Ok(OneINIAST::new(create_body(pairs.next().unwrap()))),
-		Err(e) => Err(e),
-	};
+pub fn parse(contents: &str) -> Result<OneINIAST, ParseError> {
+	Ok(OneINIAST::new(create_body(contents, None)?))
+}
+
+/// Parses [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+/// contents without giving up at the first invalid line.
+///
+/// Each line that cannot be parsed is kept as an [`Item::Error`] node in the
+/// prelude or section it appears in, and reported in the returned list of
+/// errors, in order. The rest of the contents parse as if the invalid lines
+/// were blank.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{Item, ParseErrorKind};
+///
+/// let (ast, errors) = one_ini::parse_recovering("[*]\nindent_style\nindent_size=2\n");
+///
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].kind, ParseErrorKind::MissingEquals);
+/// assert_eq!(errors[0].line, 2);
+///
+/// let Item::Section(section) = &ast.body[0] else { panic!() };
+/// assert!(matches!(&section.body[0], Item::Error(invalid) if invalid.text == "indent_style"));
+/// assert!(matches!(&section.body[1], Item::Pair(pair) if pair.value == "2"));
+/// ```
+pub fn parse_recovering(contents: &str) -> (OneINIAST, Vec<ParseError>) {
+	let mut errors = vec![];
+	// Invalid lines are collected as errors rather than returned.
+	let body = create_body(contents, Some(&mut errors)).unwrap();
+	(OneINIAST::new(body), errors)
+}
+
+fn line_end(contents: &str, offset: usize) -> usize {
+	contents[offset..]
+		.find(['\r', '\n'])
+		.map_or(contents.len(), |i| offset + i)
 }
 
-fn create_body(pair: pest::iterators::Pair<'_, Rule>) -> Vec<Item> {
-	return pair
-		.into_inner()
-		.filter(|p| match p.as_rule() {
-			Rule::EOI => false,
-			_ => true,
-		})
-		.map(|p| match p.as_rule() {
-			Rule::section => {
-				let mut inner_rules = p.into_inner();
-				let header = inner_rules.next().unwrap().into_inner().next().unwrap();
-				return Item::Section(Section {
-					name: String::from(header.as_str()),
-					body: match inner_rules.next() {
-						Some(pair) => create_body(pair),
-						_ => vec![],
-					},
+/// Builds the items of the prelude and the sections from the lines of the
+/// contents. Invalid lines become [`Item::Error`] nodes when `errors`
+/// collects them, and fail the parse otherwise.
+fn create_body(
+	contents: &str,
+	mut errors: Option<&mut Vec<ParseError>>,
+) -> Result<Vec<Item>, ParseError> {
+	let lines = LineIndex::new(contents);
+	let mut body = vec![];
+	let mut section: Option<Section> = None;
+	for line in Lexer::new(contents) {
+		let item = match line {
+			Lexeme::Section { name } => {
+				body.extend(section.take().map(Item::Section));
+				section = Some(Section {
+					name: String::from(&contents[name.clone()]),
+					body: vec![],
+					// The brackets are single bytes around the name.
+					span: Some(lines.span(name.start - 1, name.end + 1)),
+					name_span: Some(lines.span(name.start, name.end)),
 				});
+				continue;
 			}
-			Rule::pair => {
-				let mut inner_rules = p.into_inner();
-				return Item::Pair(Pair {
-					key: String::from(inner_rules.next().unwrap().as_str()),
-					value: String::from(inner_rules.next().unwrap().as_str()),
+			Lexeme::Pair { key, value } => Item::Pair(Pair {
+				key: String::from(&contents[key.clone()]),
+				value: String::from(&contents[value.clone()]),
+				span: Some(lines.span(key.start, value.end)),
+				key_span: Some(lines.span(key.start, key.end)),
+				value_span: Some(lines.span(value.start, value.end)),
+			}),
+			Lexeme::Comment { indicator, value } => Item::Comment(Comment {
+				indicator: char::from(contents.as_bytes()[indicator]),
+				value: String::from(&contents[value.clone()]),
+				span: Some(lines.span(indicator, value.end)),
+			}),
+			Lexeme::Invalid { text } => {
+				let error = ParseError::invalid_line(contents, &lines, text.start);
+				let Some(errors) = errors.as_deref_mut() else {
+					return Err(error);
+				};
+				let text = contents[error.offset..line_end(contents, error.offset)]
+					.trim_end_matches([' ', '\t']);
+				let span = lines.span(error.offset, error.offset + text.len());
+				let item = Item::Error(Invalid {
+					text: String::from(text),
+					kind: error.kind,
+					span: Some(span),
 				});
+				errors.push(error);
+				item
 			}
-			Rule::comment => {
-				let mut inner_rules = p.into_inner();
-				return Item::Comment(Comment {
-					indicator: inner_rules.next().unwrap().as_str().chars().nth(0).unwrap(),
-					value: String::from(inner_rules.next().unwrap().as_str()),
-				});
+		};
+		match &mut section {
+			Some(section) => {
+				// A section spans its header and every item of its body.
+				if let (Some(span), Some(item_span)) = (&mut section.span, item.span()) {
+					span.end = item_span.end;
+				}
+				section.body.push(item);
 			}
-			_ => unreachable!(),
-		})
-		.collect();
+			None => body.push(item),
+		}
+	}
+	body.extend(section.map(Item::Section));
+	Ok(body)
 }
 
 /// The root [AST](https://en.wikipedia.org/wiki/Abstract_syntax_tree) node of
@@ -193,28 +406,9 @@ fn create_body(pair: pest::iterators::Pair<'_, Rule>) -> Vec<Item> {
 /// use one_ini::*;
 ///
 /// let ast = OneINIAST::new(vec![
-///     Item::Pair(Pair {
-///         key: String::from("root"),
-///         value: String::from("true"),
-///     }),
-///     Item::Section(Section {
-///         name: String::from("one"),
-///         body: vec![
-///             Item::Comment(Comment {
-///                 indicator: '#',
-///                 value: String::from(" body1"),
-///             }),
-///         ],
-///     }),
-///     Item::Section(Section {
-///         name: String::from("two"),
-///         body: vec![
-///             Item::Comment(Comment {
-///                 indicator: ';',
-///                 value: String::from(" body2"),
-///             }),
-///         ],
-///     }),
+///     Item::Pair(Pair::new("root", "true")),
+///     Item::Section(Section::new("one", vec![Item::Comment(Comment::new('#', " body1"))])),
+///     Item::Section(Section::new("two", vec![Item::Comment(Comment::new(';', " body2"))])),
 /// ]);
 ///
 /// assert_eq!(ast.to_string(), "root=true\n\n[one]\n# body1\n\n[two]\n; body2\n");
@@ -243,24 +437,33 @@ impl OneINIAST {
 			body: body.into(),
 		}
 	}
+
+	/// Returns `true` if the prelude contains a `root=true` pair, which marks
+	/// the file as the top-most EditorConfig file. Both the key and the value
+	/// are case-insensitive.
+	///
+	/// # Example
+	///
+	/// ```
+	/// assert!(one_ini::parse("ROOT = True\n[*]\n").unwrap().is_root());
+	/// assert!(!one_ini::parse("[*]\nroot=true\n").unwrap().is_root());
+	/// ```
+	pub fn is_root(&self) -> bool {
+		self.body
+			.iter()
+			.take_while(|item| !matches!(item, Item::Section(_)))
+			.any(|item| match item {
+				Item::Pair(pair) => {
+					pair.key.eq_ignore_ascii_case("root") && pair.value.eq_ignore_ascii_case("true")
+				}
+				_ => false,
+			})
+	}
 }
 
 impl fmt::Display for OneINIAST {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		let mut wrote = false;
-		for item in &self.body {
-			match item {
-				Item::Section(_section) => {
-					if wrote {
-						writeln!(formatter)?;
-					}
-				}
-				_ => (),
-			}
-			item.fmt(formatter)?;
-			wrote = true;
-		}
-		Ok(())
+		Printer::new(&FormatOptions::default()).ast(formatter, self)
 	}
 }
 
@@ -272,10 +475,7 @@ impl fmt::Display for OneINIAST {
 /// ```
 /// use one_ini::{Comment,Item};
 ///
-/// let item = Item::Comment(Comment {
-///     indicator: '#',
-///     value: String::from("octothorpe"),
-/// });
+/// let item = Item::Comment(Comment::new('#', "octothorpe"));
 /// let serialized = serde_json::to_string(&item).unwrap();
 /// assert_eq!(
 ///     serialized,
@@ -292,16 +492,56 @@ pub enum Item {
 	Comment(Comment),
 	Pair(Pair),
 	Section(Section),
+	/// A line that could not be parsed, only produced by [parse_recovering].
+	Error(Invalid),
+}
+
+impl Item {
+	/// The span of the comment, pair, section or invalid line, if it was
+	/// parsed.
+	pub fn span(&self) -> Option<Span> {
+		match self {
+			Item::Comment(comment) => comment.span,
+			Item::Pair(pair) => pair.span,
+			Item::Section(section) => section.span,
+			Item::Error(invalid) => invalid.span,
+		}
+	}
 }
 
 impl fmt::Display for Item {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		match self {
-			Item::Comment(comment) => comment.fmt(formatter),
-			Item::Pair(pair) => pair.fmt(formatter),
-			Item::Section(section) => section.fmt(formatter),
-		}?;
-		Ok(())
+		Printer::new(&FormatOptions::default()).item(formatter, self, 0)
+	}
+}
+
+/// A line that is neither blank, a comment, a pair nor a section header,
+/// kept verbatim by [parse_recovering].
+///
+/// # Example
+///
+/// ```
+/// let (ast, _) = one_ini::parse_recovering("  [*.md\n");
+/// let one_ini::Item::Error(invalid) = &ast.body[0] else { panic!() };
+///
+/// assert_eq!(invalid.text, "[*.md");
+/// assert_eq!(invalid.kind, one_ini::ParseErrorKind::UnterminatedSectionHeader);
+/// assert_eq!(invalid.to_string(), "[*.md\n");
+/// ```
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Invalid {
+	/// The text of the line, without surrounding whitespace.
+	pub text: String,
+	/// Why the line could not be parsed.
+	pub kind: ParseErrorKind,
+	/// Where the line was parsed from, excluding surrounding whitespace.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub span: Option<Span>,
+}
+
+impl fmt::Display for Invalid {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		Printer::new(&FormatOptions::default()).invalid(formatter, self)
 	}
 }
 
@@ -311,19 +551,13 @@ impl fmt::Display for Item {
 /// # Examples
 ///
 /// ```
-/// let comment = one_ini::Comment {
-///     indicator: '#',
-///     value: String::from("octothorpe"),
-/// };
+/// let comment = one_ini::Comment::new('#', "octothorpe");
 ///
 /// assert_eq!(comment.to_string(), "#octothorpe\n");
 /// ```
 ///
 /// ```
-/// let comment = one_ini::Comment {
-///     indicator: ';',
-///     value: String::from("semi-colon"),
-/// };
+/// let comment = one_ini::Comment::new(';', "semi-colon");
 ///
 /// assert_eq!(comment.to_string(), ";semi-colon\n");
 /// ```
@@ -331,10 +565,7 @@ impl fmt::Display for Item {
 /// # Serializing & Deserializing
 ///
 /// ```
-/// let comment = one_ini::Comment {
-///     indicator: '#',
-///     value: String::from("octothorpe"),
-/// };
+/// let comment = one_ini::Comment::new('#', "octothorpe");
 /// let serialized = serde_json::to_string(&comment).unwrap();
 /// let deserialized: one_ini::Comment = serde_json::from_str(&serialized).unwrap();
 ///
@@ -345,13 +576,29 @@ impl fmt::Display for Item {
 /// assert_eq!(deserialized.indicator, '#');
 /// assert_eq!(deserialized.value, "octothorpe");
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[non_exhaustive]
 pub struct Comment {
 	/// The character that begins a comment. This may only be
 	/// an octothorpe (`#`) or a semi-colon (`;`).
 	pub indicator: char,
 	/// The value that follows the comment indicator.
 	pub value: String,
+	/// Where the comment was parsed from, from the indicator to the end of
+	/// the value.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub span: Option<Span>,
+}
+
+impl Comment {
+	/// A comment that was not parsed, without a span.
+	pub fn new<V: Into<String>>(indicator: char, value: V) -> Self {
+		Comment {
+			indicator,
+			value: value.into(),
+			span: None,
+		}
+	}
 }
 
 /// Serializes a comment as a JSON string.
@@ -359,10 +606,7 @@ pub struct Comment {
 /// # Example
 ///
 /// ```
-/// let comment = one_ini::Comment {
-///     indicator: '#',
-///     value: String::from("octothorpe"),
-/// };
+/// let comment = one_ini::Comment::new('#', "octothorpe");
 /// let serialized = serde_json::to_string(&comment).unwrap();
 /// let deserialized: one_ini::Comment = serde_json::from_str(&serialized).unwrap();
 ///
@@ -386,10 +630,10 @@ pub struct Comment {
 // 	}
 // }
 
+#[allow(clippy::empty_line_after_doc_comments)]
 impl fmt::Display for Comment {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		writeln!(formatter, "{}{}", self.indicator, self.value)?;
-		Ok(())
+		Printer::new(&FormatOptions::default()).comment(formatter, self)
 	}
 }
 
@@ -398,25 +642,67 @@ impl fmt::Display for Comment {
 /// # Example
 ///
 /// ```
-/// let pair = one_ini::Pair {
-///     key: String::from("left"),
-///     value: String::from("right"),
-/// };
+/// let pair = one_ini::Pair::new("left", "right");
 ///
 /// assert_eq!(pair.to_string(), "left=right\n");
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[non_exhaustive]
 pub struct Pair {
 	/// Appears on the _left_ side of the assignment (`=`).
 	pub key: String,
 	/// Appears on the _right_ side of the assignment (`=`).
 	pub value: String,
+	/// Where the pair was parsed from, from the start of the key to the end
+	/// of the value.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub span: Option<Span>,
+	/// Where the key was parsed from.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub key_span: Option<Span>,
+	/// Where the value was parsed from.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value_span: Option<Span>,
+}
+
+impl Pair {
+	/// A pair that was not parsed, without spans.
+	pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
+		Pair {
+			key: key.into(),
+			value: value.into(),
+			span: None,
+			key_span: None,
+			value_span: None,
+		}
+	}
+
+	/// Checks that a key and value can be written as a pair that parses back
+	/// to the same key and value, returning why they cannot otherwise.
+	pub(crate) fn check(key: &str, value: &str) -> Result<(), &'static str> {
+		let valid_key = !key.is_empty()
+			&& !key.starts_with(['[', '#', ';'])
+			&& !key.contains(['=', '\r', '\n'])
+			&& key.trim_matches([' ', '\t']) == key;
+		if !valid_key {
+			return Err(
+				"invalid key, which must not be empty, contain `=` or a line break, start with \
+				 `[`, `#` or `;`, or start or end with whitespace",
+			);
+		}
+		if value.contains(['\r', '\n']) {
+			return Err("value contains a line break");
+		}
+		if value.trim_matches([' ', '\t']) != value {
+			return Err("value starts or ends with whitespace, which would be trimmed");
+		}
+		Ok(())
+	}
 }
 
 impl fmt::Display for Pair {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		writeln!(formatter, "{}={}", self.key, self.value)?;
-		Ok(())
+		Printer::new(&FormatOptions::default()).pair(formatter, self, 0)
 	}
 }
 
@@ -427,27 +713,19 @@ impl fmt::Display for Pair {
 /// ```
 /// use one_ini::*;
 ///
-/// let section = Section {
-///     name: String::from("header"),
-///     body: vec![
-///         Item::Comment(Comment {
-///             indicator: '#',
-///             value: String::from(" body"),
-///         }),
-///         Item::Pair(Pair {
-///             key: String::from("left"),
-///             value: String::from("right"),
-///         }),
-///         Item::Pair(Pair {
-///             key: String::from("empty"),
-///             value: String::from(""),
-///         }),
+/// let section = Section::new(
+///     "header",
+///     vec![
+///         Item::Comment(Comment::new('#', " body")),
+///         Item::Pair(Pair::new("left", "right")),
+///         Item::Pair(Pair::new("empty", "")),
 ///     ],
-/// };
+/// );
 ///
 /// assert_eq!(section.to_string(), "[header]\n# body\nleft=right\nempty=\n");
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[non_exhaustive]
 pub struct Section {
 	/// The section header's name (i.e., the part between `[` and `]`).,
 	pub name: String,
@@ -455,15 +733,55 @@ pub struct Section {
 	/// comments and pairs.
 	#[serde(skip_serializing_if = "Vec::is_empty")]
 	pub body: Vec<Item>,
+	/// Where the section was parsed from, from the header's `[` to the end of
+	/// its last item.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub span: Option<Span>,
+	/// Where the name was parsed from.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name_span: Option<Span>,
+}
+
+impl Section {
+	/// A section that was not parsed, without spans.
+	pub fn new<N: Into<String>, B: Into<Vec<Item>>>(name: N, body: B) -> Self {
+		Section {
+			name: name.into(),
+			body: body.into(),
+			span: None,
+			name_span: None,
+		}
+	}
+
+	/// Checks that a name can be written as a section header that parses
+	/// back to the same name, returning why it cannot otherwise.
+	pub(crate) fn check_name(name: &str) -> Result<(), &'static str> {
+		match name.is_empty() || name.contains(['\r', '\n']) {
+			true => Err("invalid section name, which must not be empty or contain a line break"),
+			false => Ok(()),
+		}
+	}
+
+	/// Compiles the section's name into a [glob](Glob) that tests whether
+	/// the section applies to a path.
+	///
+	/// # Example
+	///
+	/// ```
+	/// let ast = one_ini::parse("[*.{md,txt}]\n").unwrap();
+	/// let one_ini::Item::Section(section) = &ast.body[0] else { panic!() };
+	///
+	/// assert!(section.glob().is_match("docs/README.md"));
+	/// assert!(!section.glob().is_match("src/lib.rs"));
+	/// ```
+	pub fn glob(&self) -> Glob {
+		Glob::new(&self.name)
+	}
 }
 
 impl fmt::Display for Section {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		writeln!(formatter, "[{}]", self.name)?;
-		for item in &self.body {
-			item.fmt(formatter)?;
-		}
-		Ok(())
+		Printer::new(&FormatOptions::default()).section(formatter, self)
 	}
 }
 
@@ -0,0 +1,280 @@
+//! # Linting
+//!
+//! Reports problems in a [parsed](crate::parse) `.editorconfig` file that are
+//! valid INI, but that the
+//! [EditorConfig specification](https://spec.editorconfig.org/) ignores or
+//! does not define.
+
+use crate::{Invalid, Item, OneINIAST, Pair, Property, PropertyError, Section, Span};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// How serious a [Diagnostic] is.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	/// The line is invalid, or will be ignored by EditorConfig cores.
+	Error,
+	/// The line has an effect, but probably not the intended one.
+	Warning,
+	/// The line is valid, but not understood by every EditorConfig core.
+	Info,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(match self {
+			Severity::Error => "error",
+			Severity::Warning => "warning",
+			Severity::Info => "info",
+		})
+	}
+}
+
+/// What a [Diagnostic] reports. Each code has a stable, kebab-case name that
+/// may be used to filter diagnostics.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum DiagnosticCode {
+	/// A line that could not be parsed, kept by
+	/// [parse_recovering](crate::parse_recovering).
+	InvalidLine,
+	/// A key set more than once in the same section or prelude.
+	DuplicateKey,
+	/// A section name used more than once in the same file.
+	DuplicateSection,
+	/// `root` set within a section, where it has no effect.
+	MisplacedRoot,
+	/// A pair other than `root` before the first section, where it has no
+	/// effect.
+	PreludePair,
+	/// A key that is not one of the
+	/// [supported properties](https://spec.editorconfig.org/#supported-pairs).
+	UnknownProperty,
+	/// A value that a supported property does not accept.
+	InvalidValue,
+}
+
+impl DiagnosticCode {
+	/// The stable name of the code, such as `duplicate-key`.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			DiagnosticCode::InvalidLine => "invalid-line",
+			DiagnosticCode::DuplicateKey => "duplicate-key",
+			DiagnosticCode::DuplicateSection => "duplicate-section",
+			DiagnosticCode::MisplacedRoot => "misplaced-root",
+			DiagnosticCode::PreludePair => "prelude-pair",
+			DiagnosticCode::UnknownProperty => "unknown-property",
+			DiagnosticCode::InvalidValue => "invalid-value",
+		}
+	}
+
+	/// The severity diagnostics with this code are reported with.
+	pub fn severity(&self) -> Severity {
+		match self {
+			DiagnosticCode::InvalidLine
+			| DiagnosticCode::InvalidValue
+			| DiagnosticCode::MisplacedRoot
+			| DiagnosticCode::PreludePair => Severity::Error,
+			DiagnosticCode::DuplicateKey | DiagnosticCode::DuplicateSection => Severity::Warning,
+			DiagnosticCode::UnknownProperty => Severity::Info,
+		}
+	}
+}
+
+impl fmt::Display for DiagnosticCode {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(self.as_str())
+	}
+}
+
+/// A problem found by [lint].
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{DiagnosticCode, Severity};
+///
+/// let ast = one_ini::parse("[*]\nindent_style = tabz\n").unwrap();
+/// let diagnostics = one_ini::lint(&ast);
+///
+/// assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidValue);
+/// assert_eq!(diagnostics[0].severity, Severity::Error);
+/// assert_eq!(
+///     diagnostics[0].to_string(),
+///     "error[invalid-value]: `tabz` is not a valid `indent_style`, expected `tab` or `space` at line 2, column 16",
+/// );
+/// ```
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	pub code: DiagnosticCode,
+	/// A human-readable description of the problem, without its location.
+	pub message: String,
+	/// The part of the contents the problem was found in, if the AST was
+	/// parsed.
+	pub span: Option<Span>,
+}
+
+impl Diagnostic {
+	fn new(code: DiagnosticCode, message: String, span: Option<Span>) -> Self {
+		Diagnostic {
+			severity: code.severity(),
+			code,
+			message,
+			span,
+		}
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			formatter,
+			"{}[{}]: {}",
+			self.severity, self.code, self.message
+		)?;
+		if let Some(span) = self.span {
+			write!(
+				formatter,
+				" at line {}, column {}",
+				span.start.line, span.start.column
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Checks a parsed `.editorconfig` file for problems, returning them in the
+/// order they appear in.
+///
+/// Keys and section names are compared as EditorConfig cores compare them:
+/// keys case-insensitively, section names exactly.
+///
+/// # Example
+///
+/// ```
+/// let ast = one_ini::parse("indent_size=2\n[*]\nroot=true\n[*]\n").unwrap();
+/// let codes: Vec<&str> = one_ini::lint(&ast).iter().map(|d| d.code.as_str()).collect();
+///
+/// assert_eq!(codes, ["prelude-pair", "misplaced-root", "duplicate-section"]);
+/// ```
+pub fn lint(ast: &OneINIAST) -> Vec<Diagnostic> {
+	let mut diagnostics = vec![];
+	let mut prelude_keys = HashSet::new();
+	let mut sections = HashSet::new();
+	for item in &ast.body {
+		match item {
+			Item::Pair(pair) => {
+				lint_duplicate(pair, &mut prelude_keys, &mut diagnostics);
+				lint_prelude_pair(pair, &mut diagnostics);
+			}
+			Item::Section(section) => {
+				if !sections.insert(section.name.as_str()) {
+					diagnostics.push(Diagnostic::new(
+						DiagnosticCode::DuplicateSection,
+						format!("section `[{}]` is already declared", section.name),
+						section.name_span,
+					));
+				}
+				lint_section(section, &mut diagnostics);
+			}
+			Item::Error(invalid) => diagnostics.push(invalid_line(invalid)),
+			Item::Comment(_) => (),
+		}
+	}
+	diagnostics
+}
+
+fn lint_section(section: &Section, diagnostics: &mut Vec<Diagnostic>) {
+	let mut keys = HashSet::new();
+	for item in &section.body {
+		match item {
+			Item::Pair(pair) => {
+				lint_duplicate(pair, &mut keys, diagnostics);
+				lint_pair(pair, diagnostics);
+			}
+			Item::Error(invalid) => diagnostics.push(invalid_line(invalid)),
+			_ => (),
+		}
+	}
+}
+
+fn lint_duplicate(pair: &Pair, keys: &mut HashSet<String>, diagnostics: &mut Vec<Diagnostic>) {
+	if !keys.insert(pair.key.to_lowercase()) {
+		diagnostics.push(Diagnostic::new(
+			DiagnosticCode::DuplicateKey,
+			format!(
+				"`{}` is already set, and overrides the earlier value",
+				pair.key
+			),
+			pair.key_span,
+		));
+	}
+}
+
+fn lint_prelude_pair(pair: &Pair, diagnostics: &mut Vec<Diagnostic>) {
+	match pair.property() {
+		Ok(Property::Root(_)) => (),
+		Err(PropertyError::InvalidValue { expected }) if pair.key.eq_ignore_ascii_case("root") => {
+			diagnostics.push(invalid_value(pair, expected));
+		}
+		_ => diagnostics.push(Diagnostic::new(
+			DiagnosticCode::PreludePair,
+			format!(
+				"`{}` is set before the first section, where only `root` has an effect",
+				pair.key
+			),
+			pair.key_span,
+		)),
+	}
+}
+
+fn lint_pair(pair: &Pair, diagnostics: &mut Vec<Diagnostic>) {
+	if pair.key.eq_ignore_ascii_case("root") {
+		diagnostics.push(Diagnostic::new(
+			DiagnosticCode::MisplacedRoot,
+			String::from("`root` has no effect within a section"),
+			pair.key_span,
+		));
+		return;
+	}
+	match pair.property() {
+		Ok(_) => (),
+		Err(PropertyError::UnknownProperty) => diagnostics.push(Diagnostic::new(
+			DiagnosticCode::UnknownProperty,
+			format!("`{}` is not a supported property", pair.key),
+			pair.key_span,
+		)),
+		Err(PropertyError::InvalidValue { expected }) => {
+			diagnostics.push(invalid_value(pair, expected))
+		}
+	}
+}
+
+fn invalid_value(pair: &Pair, expected: &str) -> Diagnostic {
+	Diagnostic::new(
+		DiagnosticCode::InvalidValue,
+		format!(
+			"`{}` is not a valid `{}`, expected {}",
+			pair.value,
+			pair.key.to_lowercase(),
+			expected
+		),
+		pair.value_span,
+	)
+}
+
+fn invalid_line(invalid: &Invalid) -> Diagnostic {
+	Diagnostic::new(
+		DiagnosticCode::InvalidLine,
+		format!(
+			"`{}` is not a valid line, {}",
+			invalid.text,
+			invalid.kind.message()
+		),
+		invalid.span,
+	)
+}
@@ -0,0 +1,135 @@
+//! # Config lookup
+//!
+//! Finds the `.editorconfig` files that apply to a file by walking up its
+//! parent directories, stopping at the first file marked `root=true`.
+
+use crate::{parse_recovering, resolve_for_version, Item, OneINIAST, Properties, Version};
+use std::path::{Path, PathBuf};
+use std::{error, fmt, fs, io};
+
+/// The name of the files [`resolve_file`] looks for.
+pub const DEFAULT_FILE_NAME: &str = ".editorconfig";
+
+/// An error raised while reading a config file during lookup.
+#[derive(Debug)]
+pub enum LookupError {
+	/// The config file exists but could not be read.
+	Io { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for LookupError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			LookupError::Io { path, source } => {
+				write!(formatter, "failed to read {}: {}", path.display(), source)
+			}
+		}
+	}
+}
+
+impl error::Error for LookupError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			LookupError::Io { source, .. } => Some(source),
+		}
+	}
+}
+
+/// Finds and parses every config file named `file_name` that applies to
+/// `path`, starting in the directory containing `path` and walking up until
+/// a file marked [`root=true`](OneINIAST::is_root) or the file system root
+/// is reached. Relative paths are made absolute against the current
+/// directory first.
+///
+/// The configs are returned together with their directories, ordered from
+/// the farthest to the nearest, ready to be passed to [`resolve`](crate::resolve).
+/// Directories without a config file are skipped, as are the lines of a
+/// config that cannot be parsed, so that one malformed line does not disable
+/// the rest of the file.
+pub fn find_configs<P: AsRef<Path>>(
+	path: P,
+	file_name: &str,
+) -> Result<Vec<(PathBuf, OneINIAST)>, LookupError> {
+	let path = absolute(path.as_ref())?;
+	let mut configs = vec![];
+	for dir in path.ancestors().skip(1) {
+		let config_path = dir.join(file_name);
+		let contents = match fs::read_to_string(&config_path) {
+			Ok(contents) => contents,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+			Err(source) => {
+				return Err(LookupError::Io {
+					path: config_path,
+					source,
+				})
+			}
+		};
+		let (mut ast, _) = parse_recovering(&contents);
+		remove_errors(&mut ast.body);
+		let is_root = ast.is_root();
+		configs.push((dir.to_path_buf(), ast));
+		if is_root {
+			break;
+		}
+	}
+	configs.reverse();
+	Ok(configs)
+}
+
+/// Resolves the properties that apply to `path` from the `.editorconfig`
+/// files found in its directory and above.
+///
+/// # Example
+///
+/// ```no_run
+/// let properties = one_ini::resolve_file("src/lib.rs").unwrap();
+/// println!("{}", properties);
+/// ```
+pub fn resolve_file<P: AsRef<Path>>(path: P) -> Result<Properties, LookupError> {
+	resolve_file_with(path, DEFAULT_FILE_NAME, Version::CURRENT)
+}
+
+/// Resolves the properties that apply to `path` from the config files named
+/// `file_name` found in its directory and above, as the given version of an
+/// EditorConfig core would.
+///
+/// # Example
+///
+/// ```no_run
+/// use one_ini::Version;
+///
+/// let properties = one_ini::resolve_file_with("src/lib.rs", ".editorconfig", Version::new(0, 9, 0));
+/// ```
+pub fn resolve_file_with<P: AsRef<Path>>(
+	path: P,
+	file_name: &str,
+	version: Version,
+) -> Result<Properties, LookupError> {
+	let path = absolute(path.as_ref())?;
+	let configs = find_configs(&path, file_name)?;
+	let configs: Vec<(&Path, &OneINIAST)> = configs
+		.iter()
+		.map(|(dir, ast)| (dir.as_path(), ast))
+		.collect();
+	Ok(resolve_for_version(&path, &configs, version))
+}
+
+/// Removes the lines that could not be parsed from a body and the bodies of
+/// its sections.
+fn remove_errors(body: &mut Vec<Item>) {
+	body.retain_mut(|item| match item {
+		Item::Error(_) => false,
+		Item::Section(section) => {
+			remove_errors(&mut section.body);
+			true
+		}
+		_ => true,
+	});
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, LookupError> {
+	std::path::absolute(path).map_err(|source| LookupError::Io {
+		path: path.to_path_buf(),
+		source,
+	})
+}
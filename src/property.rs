@@ -0,0 +1,449 @@
+//! # Typed properties
+//!
+//! Interprets the values of the
+//! [supported properties](https://spec.editorconfig.org/#supported-pairs),
+//! which are matched case-insensitively, along with the special `unset`
+//! value.
+
+use crate::{Pair, Properties};
+use serde::{Deserialize, Serialize};
+use std::{error, fmt};
+
+/// Either a value, or `unset`, which removes the effect of a property even
+/// if it was set before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting<T> {
+	Value(T),
+	Unset,
+}
+
+impl<T> Setting<T> {
+	/// The value, unless the property is `unset`.
+	pub fn value(self) -> Option<T> {
+		match self {
+			Setting::Value(value) => Some(value),
+			Setting::Unset => None,
+		}
+	}
+}
+
+/// `indent_style`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+	Tab,
+	Space,
+}
+
+/// `indent_size`, the number of columns per indentation level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentSize {
+	Columns(u32),
+	/// The width of a tab, from `tab_width`.
+	Tab,
+}
+
+/// `end_of_line`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EndOfLine {
+	Lf,
+	Cr,
+	Crlf,
+}
+
+/// `charset`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+	Latin1,
+	Utf8,
+	/// UTF-8 with a byte order mark.
+	Utf8Bom,
+	Utf16Be,
+	Utf16Le,
+}
+
+/// `max_line_length`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaxLineLength {
+	Columns(u32),
+	Off,
+}
+
+/// A supported property with its interpreted value.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{IndentSize, Property, Setting};
+///
+/// assert_eq!(
+///     Property::new("Indent_Size", "TAB"),
+///     Ok(Property::IndentSize(Setting::Value(IndentSize::Tab))),
+/// );
+/// assert_eq!(
+///     Property::new("tab_width", "unset"),
+///     Ok(Property::TabWidth(Setting::Unset)),
+/// );
+/// assert_eq!(
+///     Property::new("indent_style", "tabz").unwrap_err().to_string(),
+///     "invalid value, expected `tab` or `space`",
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Property {
+	IndentStyle(Setting<IndentStyle>),
+	IndentSize(Setting<IndentSize>),
+	TabWidth(Setting<u32>),
+	EndOfLine(Setting<EndOfLine>),
+	Charset(Setting<Charset>),
+	TrimTrailingWhitespace(Setting<bool>),
+	InsertFinalNewline(Setting<bool>),
+	MaxLineLength(Setting<MaxLineLength>),
+	/// A language tag such as `en-US`, kept as written.
+	SpellingLanguage(Setting<String>),
+	/// `root`, which may only be set in the prelude, and cannot be `unset`.
+	Root(bool),
+}
+
+/// Why a pair is not a [Property].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyError {
+	/// The key is not one of the supported properties.
+	UnknownProperty,
+	/// The property does not accept the value.
+	InvalidValue {
+		/// The values that are accepted, such as ``"`tab` or `space`"``.
+		expected: &'static str,
+	},
+}
+
+impl fmt::Display for PropertyError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PropertyError::UnknownProperty => formatter.write_str("unknown property"),
+			PropertyError::InvalidValue { expected } => {
+				write!(formatter, "invalid value, expected {}", expected)
+			}
+		}
+	}
+}
+
+impl error::Error for PropertyError {}
+
+const BOOLEAN: &str = "`true` or `false`";
+const NUMBER: &str = "a positive integer";
+
+impl Property {
+	/// The keys of the supported properties, in the order the specification
+	/// lists them.
+	pub const KEYS: [&'static str; 10] = [
+		"indent_style",
+		"indent_size",
+		"tab_width",
+		"end_of_line",
+		"charset",
+		"spelling_language",
+		"trim_trailing_whitespace",
+		"insert_final_newline",
+		"max_line_length",
+		"root",
+	];
+
+	/// The keywords a property accepts, other than numbers, language tags and
+	/// `unset`, for suggesting values.
+	///
+	/// # Example
+	///
+	/// ```
+	/// assert_eq!(one_ini::Property::keywords("Indent_Size"), ["tab"]);
+	/// assert!(one_ini::Property::keywords("unknown").is_empty());
+	/// ```
+	pub fn keywords(key: &str) -> &'static [&'static str] {
+		match key.to_lowercase().as_str() {
+			"indent_style" => &["tab", "space"],
+			"indent_size" => &["tab"],
+			"end_of_line" => &["lf", "cr", "crlf"],
+			"charset" => &["latin1", "utf-8", "utf-8-bom", "utf-16be", "utf-16le"],
+			"trim_trailing_whitespace" | "insert_final_newline" | "root" => &["true", "false"],
+			"max_line_length" => &["off"],
+			_ => &[],
+		}
+	}
+
+	/// Interprets a key and value, ignoring their case.
+	pub fn new(key: &str, value: &str) -> Result<Self, PropertyError> {
+		let property = match key.to_lowercase().as_str() {
+			"indent_style" => {
+				Property::IndentStyle(setting(value, "`tab` or `space`", |v| match v {
+					"tab" => Some(IndentStyle::Tab),
+					"space" => Some(IndentStyle::Space),
+					_ => None,
+				})?)
+			}
+			"indent_size" => Property::IndentSize(setting(
+				value,
+				"`tab` or a positive integer",
+				|v| match v {
+					"tab" => Some(IndentSize::Tab),
+					v => number(v).map(IndentSize::Columns),
+				},
+			)?),
+			"tab_width" => Property::TabWidth(setting(value, NUMBER, number)?),
+			"end_of_line" => {
+				Property::EndOfLine(setting(value, "`lf`, `cr` or `crlf`", |v| match v {
+					"lf" => Some(EndOfLine::Lf),
+					"cr" => Some(EndOfLine::Cr),
+					"crlf" => Some(EndOfLine::Crlf),
+					_ => None,
+				})?)
+			}
+			"charset" => Property::Charset(setting(
+				value,
+				"`latin1`, `utf-8`, `utf-8-bom`, `utf-16be` or `utf-16le`",
+				|v| match v {
+					"latin1" => Some(Charset::Latin1),
+					"utf-8" => Some(Charset::Utf8),
+					"utf-8-bom" => Some(Charset::Utf8Bom),
+					"utf-16be" => Some(Charset::Utf16Be),
+					"utf-16le" => Some(Charset::Utf16Le),
+					_ => None,
+				},
+			)?),
+			"trim_trailing_whitespace" => {
+				Property::TrimTrailingWhitespace(setting(value, BOOLEAN, boolean)?)
+			}
+			"insert_final_newline" => {
+				Property::InsertFinalNewline(setting(value, BOOLEAN, boolean)?)
+			}
+			"max_line_length" => Property::MaxLineLength(setting(
+				value,
+				"a positive integer or `off`",
+				|v| match v {
+					"off" => Some(MaxLineLength::Off),
+					v => number(v).map(MaxLineLength::Columns),
+				},
+			)?),
+			"spelling_language" => {
+				Property::SpellingLanguage(match value.to_lowercase().as_str() {
+					"unset" => Setting::Unset,
+					_ => Setting::Value(String::from(value)),
+				})
+			}
+			"root" => Property::Root(
+				boolean(&value.to_lowercase())
+					.ok_or(PropertyError::InvalidValue { expected: BOOLEAN })?,
+			),
+			_ => return Err(PropertyError::UnknownProperty),
+		};
+		Ok(property)
+	}
+
+	/// The lowercase key of the property.
+	pub fn key(&self) -> &'static str {
+		match self {
+			Property::IndentStyle(_) => "indent_style",
+			Property::IndentSize(_) => "indent_size",
+			Property::TabWidth(_) => "tab_width",
+			Property::EndOfLine(_) => "end_of_line",
+			Property::Charset(_) => "charset",
+			Property::TrimTrailingWhitespace(_) => "trim_trailing_whitespace",
+			Property::InsertFinalNewline(_) => "insert_final_newline",
+			Property::MaxLineLength(_) => "max_line_length",
+			Property::SpellingLanguage(_) => "spelling_language",
+			Property::Root(_) => "root",
+		}
+	}
+}
+
+/// Interprets `value` with `parse` after lowercasing it, unless it is `unset`.
+fn setting<T>(
+	value: &str,
+	expected: &'static str,
+	parse: impl Fn(&str) -> Option<T>,
+) -> Result<Setting<T>, PropertyError> {
+	match value.to_lowercase().as_str() {
+		"unset" => Ok(Setting::Unset),
+		value => parse(value)
+			.map(Setting::Value)
+			.ok_or(PropertyError::InvalidValue { expected }),
+	}
+}
+
+fn boolean(value: &str) -> Option<bool> {
+	match value {
+		"true" => Some(true),
+		"false" => Some(false),
+		_ => None,
+	}
+}
+
+fn number(value: &str) -> Option<u32> {
+	if !value.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+	value.parse().ok().filter(|&n| n > 0)
+}
+
+impl Pair {
+	/// Interprets the pair as a supported property.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use one_ini::{EndOfLine, Pair, Property, Setting};
+	///
+	/// let pair = Pair::new("end_of_line", "CRLF");
+	///
+	/// assert_eq!(pair.property(), Ok(Property::EndOfLine(Setting::Value(EndOfLine::Crlf))));
+	/// ```
+	pub fn property(&self) -> Result<Property, PropertyError> {
+		Property::new(&self.key, &self.value)
+	}
+}
+
+/// Typed accessors, which return `None` for properties that are missing,
+/// `unset` or invalid.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{IndentSize, IndentStyle};
+///
+/// let mut properties = one_ini::Properties::new();
+/// properties.insert("indent_style", "Tab");
+/// properties.insert("tab_width", "8");
+///
+/// assert_eq!(properties.indent_style(), Some(IndentStyle::Tab));
+/// assert_eq!(properties.indent_size(), Some(IndentSize::Columns(8)));
+/// assert_eq!(properties.max_line_length(), None);
+/// ```
+impl Properties {
+	fn property(&self, key: &str) -> Option<Property> {
+		Property::new(key, self.get(key)?).ok()
+	}
+
+	pub fn indent_style(&self) -> Option<IndentStyle> {
+		match self.property("indent_style")? {
+			Property::IndentStyle(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	/// The indentation size, which defaults to `tab` when `indent_style` is
+	/// `tab`. A size of `tab` is the `tab_width`, if that is set.
+	pub fn indent_size(&self) -> Option<IndentSize> {
+		let indent_size = match self.property("indent_size") {
+			Some(Property::IndentSize(setting)) => setting.value(),
+			// An explicit `unset` or an invalid value is not defaulted.
+			_ if self.get("indent_size").is_some() => None,
+			_ if self.indent_style() == Some(IndentStyle::Tab) => Some(IndentSize::Tab),
+			_ => None,
+		};
+		match (indent_size, self.explicit_tab_width()) {
+			(Some(IndentSize::Tab), Some(tab_width)) => Some(IndentSize::Columns(tab_width)),
+			(indent_size, _) => indent_size,
+		}
+	}
+
+	/// The width of a tab, which defaults to a numeric `indent_size`.
+	pub fn tab_width(&self) -> Option<u32> {
+		if self.get("tab_width").is_some() {
+			return self.explicit_tab_width();
+		}
+		match self.property("indent_size")? {
+			Property::IndentSize(Setting::Value(IndentSize::Columns(columns))) => Some(columns),
+			_ => None,
+		}
+	}
+
+	fn explicit_tab_width(&self) -> Option<u32> {
+		match self.property("tab_width")? {
+			Property::TabWidth(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	pub fn end_of_line(&self) -> Option<EndOfLine> {
+		match self.property("end_of_line")? {
+			Property::EndOfLine(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	pub fn charset(&self) -> Option<Charset> {
+		match self.property("charset")? {
+			Property::Charset(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	pub fn trim_trailing_whitespace(&self) -> Option<bool> {
+		match self.property("trim_trailing_whitespace")? {
+			Property::TrimTrailingWhitespace(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	pub fn insert_final_newline(&self) -> Option<bool> {
+		match self.property("insert_final_newline")? {
+			Property::InsertFinalNewline(setting) => setting.value(),
+			_ => None,
+		}
+	}
+
+	pub fn max_line_length(&self) -> Option<MaxLineLength> {
+		match self.property("max_line_length")? {
+			Property::MaxLineLength(setting) => setting.value(),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for IndentStyle {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(match self {
+			IndentStyle::Tab => "tab",
+			IndentStyle::Space => "space",
+		})
+	}
+}
+
+impl fmt::Display for IndentSize {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			IndentSize::Columns(columns) => columns.fmt(formatter),
+			IndentSize::Tab => formatter.write_str("tab"),
+		}
+	}
+}
+
+impl fmt::Display for EndOfLine {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(match self {
+			EndOfLine::Lf => "lf",
+			EndOfLine::Cr => "cr",
+			EndOfLine::Crlf => "crlf",
+		})
+	}
+}
+
+impl fmt::Display for Charset {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(match self {
+			Charset::Latin1 => "latin1",
+			Charset::Utf8 => "utf-8",
+			Charset::Utf8Bom => "utf-8-bom",
+			Charset::Utf16Be => "utf-16be",
+			Charset::Utf16Le => "utf-16le",
+		})
+	}
+}
+
+impl fmt::Display for MaxLineLength {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MaxLineLength::Columns(columns) => columns.fmt(formatter),
+			MaxLineLength::Off => formatter.write_str("off"),
+		}
+	}
+}
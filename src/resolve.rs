@@ -0,0 +1,286 @@
+//! # Property resolution
+//!
+//! Applies the sections of one or more [parsed](crate::parse) `.editorconfig`
+//! files to a target file path, following the
+//! [EditorConfig specification](https://spec.editorconfig.org/#file-processing).
+
+use crate::{Item, OneINIAST, Section, Version};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::path::{Component, Path};
+
+/// Properties whose values are matched case-insensitively, and are therefore
+/// lowercased when resolved.
+const CASE_INSENSITIVE_VALUES: [&str; 6] = [
+	"charset",
+	"end_of_line",
+	"indent_size",
+	"indent_style",
+	"insert_final_newline",
+	"trim_trailing_whitespace",
+];
+
+/// The effective properties of a file, kept in the order in which each key
+/// was first declared.
+///
+/// Keys are always lowercase. Setting a key that already exists replaces its
+/// value without moving it.
+///
+/// # Example
+///
+/// ```
+/// let mut properties = one_ini::Properties::new();
+/// properties.insert("indent_style", "space");
+/// properties.insert("indent_size", "2");
+/// properties.insert("INDENT_STYLE", "tab");
+///
+/// assert_eq!(properties.get("indent_style"), Some("tab"));
+/// assert_eq!(properties.to_string(), "indent_style=tab\nindent_size=2\n");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Properties {
+	entries: Vec<(String, String)>,
+}
+
+impl Properties {
+	pub fn new() -> Self {
+		Properties::default()
+	}
+
+	/// Returns the value of a property, looking its key up case-insensitively.
+	pub fn get(&self, key: &str) -> Option<&str> {
+		let key = key.to_lowercase();
+		self.entries
+			.iter()
+			.find(|(k, _)| *k == key)
+			.map(|(_, v)| v.as_str())
+	}
+
+	/// Sets a property, returning its previous value if it was already set.
+	pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> Option<String> {
+		let key = key.into().to_lowercase();
+		let value = value.into();
+		match self.entries.iter_mut().find(|(k, _)| *k == key) {
+			Some((_, v)) => Some(std::mem::replace(v, value)),
+			None => {
+				self.entries.push((key, value));
+				None
+			}
+		}
+	}
+
+	/// Removes a property, returning its value if it was set.
+	pub fn remove(&mut self, key: &str) -> Option<String> {
+		let key = key.to_lowercase();
+		let index = self.entries.iter().position(|(k, _)| *k == key)?;
+		Some(self.entries.remove(index).1)
+	}
+
+	/// Iterates over `(key, value)` pairs in declaration order.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+		self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+impl fmt::Display for Properties {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for (key, value) in self.iter() {
+			writeln!(formatter, "{}={}", key, value)?;
+		}
+		Ok(())
+	}
+}
+
+/// Collects `(key, value)` pairs, as if each were [inserted](Properties::insert)
+/// in turn.
+///
+/// # Example
+///
+/// ```
+/// let properties: one_ini::Properties = [("Charset", "utf-8"), ("charset", "latin1")]
+///     .into_iter()
+///     .collect();
+///
+/// assert_eq!(properties.to_string(), "charset=latin1\n");
+/// ```
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Properties {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(pairs: I) -> Self {
+		let mut properties = Properties::new();
+		for (key, value) in pairs {
+			properties.insert(key, value);
+		}
+		properties
+	}
+}
+
+impl Serialize for Properties {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut map = serializer.serialize_map(Some(self.len()))?;
+		for (key, value) in self.iter() {
+			map.serialize_entry(key, value)?;
+		}
+		map.end()
+	}
+}
+
+/// Deserializes properties from a map of keys to string values, such as a
+/// JS object of resolved properties, keeping their order.
+impl<'de> Deserialize<'de> for Properties {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		struct PropertiesVisitor;
+
+		impl<'de> Visitor<'de> for PropertiesVisitor {
+			type Value = Properties;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+				formatter.write_str("a map of property names to string values")
+			}
+
+			fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+			where
+				A: MapAccess<'de>,
+			{
+				let mut properties = Properties::new();
+				while let Some((key, value)) = map.next_entry::<String, String>()? {
+					properties.insert(key, value);
+				}
+				Ok(properties)
+			}
+		}
+
+		deserializer.deserialize_map(PropertiesVisitor)
+	}
+}
+
+/// Resolves the properties that apply to `path`.
+///
+/// `configs` pairs each parsed `.editorconfig` file with the directory it
+/// lives in, ordered from the farthest (e.g., the `root=true` file) to the
+/// nearest. Sections are applied in that order, so later sections override
+/// earlier ones within a file and nearer files override farther ones. Pairs
+/// in the prelude are not properties and are ignored.
+///
+/// `path` is matched relative to each directory; configs whose directory
+/// does not contain `path` do not apply.
+///
+/// # Example
+///
+/// ```
+/// let parent = one_ini::parse("root=true\n[*]\nindent_style=space\n").unwrap();
+/// let child = one_ini::parse("[*.rs]\nindent_size=4\n").unwrap();
+///
+/// let properties = one_ini::resolve("src/lib.rs", &[("", &parent), ("src", &child)]);
+///
+/// assert_eq!(
+///     properties.to_string(),
+///     "indent_style=space\nindent_size=4\ntab_width=4\n",
+/// );
+/// ```
+pub fn resolve<P: AsRef<Path>, D: AsRef<Path>>(path: P, configs: &[(D, &OneINIAST)]) -> Properties {
+	resolve_for_version(path, configs, Version::CURRENT)
+}
+
+/// Resolves the properties that apply to `path` as the given version of an
+/// EditorConfig core would.
+///
+/// Cores older than `0.10.0` do not default `indent_size` to `tab` when
+/// `indent_style` is `tab`.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::Version;
+///
+/// let ast = one_ini::parse("[*]\nindent_style=tab\n").unwrap();
+///
+/// let properties = one_ini::resolve_for_version("a.c", &[("", &ast)], Version::new(0, 9, 0));
+/// assert_eq!(properties.to_string(), "indent_style=tab\n");
+///
+/// let properties = one_ini::resolve_for_version("a.c", &[("", &ast)], Version::new(0, 10, 0));
+/// assert_eq!(properties.to_string(), "indent_style=tab\nindent_size=tab\n");
+/// ```
+pub fn resolve_for_version<P: AsRef<Path>, D: AsRef<Path>>(
+	path: P,
+	configs: &[(D, &OneINIAST)],
+	version: Version,
+) -> Properties {
+	let mut properties = Properties::new();
+	for (dir, ast) in configs {
+		let relative = match path.as_ref().strip_prefix(dir.as_ref()) {
+			Ok(relative) => to_slash_path(relative),
+			Err(_) => continue,
+		};
+		for item in &ast.body {
+			if let Item::Section(section) = item {
+				if section.glob().is_match(&relative) {
+					apply_section(section, &mut properties);
+				}
+			}
+		}
+	}
+	apply_defaults(&mut properties, version);
+	properties
+}
+
+fn apply_section(section: &Section, properties: &mut Properties) {
+	for item in &section.body {
+		if let Item::Pair(pair) = item {
+			let key = pair.key.to_lowercase();
+			let value = if CASE_INSENSITIVE_VALUES.contains(&key.as_str()) {
+				pair.value.to_lowercase()
+			} else {
+				pair.value.clone()
+			};
+			properties.insert(key, value);
+		}
+	}
+}
+
+/// Fills in the values the specification derives from other properties.
+fn apply_defaults(properties: &mut Properties, version: Version) {
+	if properties.get("indent_style") == Some("tab")
+		&& properties.get("indent_size").is_none()
+		&& version >= Version::new(0, 10, 0)
+	{
+		properties.insert("indent_size", "tab");
+	}
+	if let Some(indent_size) = properties.get("indent_size") {
+		if indent_size != "tab" && properties.get("tab_width").is_none() {
+			let indent_size = indent_size.to_string();
+			properties.insert("tab_width", indent_size);
+		}
+	}
+	if properties.get("indent_size") == Some("tab") {
+		if let Some(tab_width) = properties.get("tab_width") {
+			let tab_width = tab_width.to_string();
+			properties.insert("indent_size", tab_width);
+		}
+	}
+}
+
+/// Joins the normal components of a relative path with `/`, regardless of
+/// the platform's separator.
+fn to_slash_path(path: &Path) -> String {
+	path.components()
+		.filter_map(|c| match c {
+			Component::Normal(part) => Some(part.to_string_lossy()),
+			_ => None,
+		})
+		.collect::<Vec<_>>()
+		.join("/")
+}
@@ -0,0 +1,473 @@
+//! # Serializing
+//!
+//! Writes Rust types as INI contents with [serde](https://serde.rs/), the
+//! reverse of [from_str](crate::from_str). The value is converted into an
+//! [AST](crate::OneINIAST), which is printed by its
+//! [`Display`](std::fmt::Display) implementation.
+
+use crate::{Item, OneINIAST, Pair, Section};
+use serde::ser::{self, Impossible, Serialize};
+use std::{error, fmt};
+
+/// An error raised by [to_string] for a value that INI cannot represent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializeError {
+	message: String,
+	/// The `.`-separated path of the key or section the problem was found in.
+	key: Option<String>,
+}
+
+impl SerializeError {
+	fn new<M: Into<String>>(message: M) -> Self {
+		SerializeError {
+			message: message.into(),
+			key: None,
+		}
+	}
+
+	/// Prefixes the path of the problem with a key.
+	fn in_key(self, key: &str) -> Self {
+		let key = match self.key {
+			Some(inner) => format!("{}.{}", key, inner),
+			None => String::from(key),
+		};
+		SerializeError {
+			key: Some(key),
+			..self
+		}
+	}
+}
+
+impl fmt::Display for SerializeError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str(&self.message)?;
+		if let Some(key) = &self.key {
+			write!(formatter, " in `{}`", key)?;
+		}
+		Ok(())
+	}
+}
+
+impl error::Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+	fn custom<T: fmt::Display>(message: T) -> Self {
+		SerializeError::new(message.to_string())
+	}
+}
+
+/// Serializes a struct or map as INI contents.
+///
+/// Fields that are values become pairs in the prelude, and fields that are
+/// structs or maps become sections, after the prelude. Fields that are
+/// `None` are left out. Values are written as [from_str](crate::from_str)
+/// reads them: `bool`s as `true` or `false`, unit enum variants by name, and
+/// sequences as their items separated by `, `.
+///
+/// Values that INI cannot represent are errors, such as sections within
+/// sections, keys or values with line breaks, and values that start or end
+/// with whitespace.
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Server {
+///     port: u16,
+///     hosts: Vec<&'static str>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     name: &'static str,
+///     server: Server,
+///     retries: Option<u8>,
+/// }
+///
+/// let config = Config {
+///     name: "api",
+///     server: Server { port: 8080, hosts: vec!["a", "b"] },
+///     retries: None,
+/// };
+///
+/// assert_eq!(
+///     one_ini::to_string(&config).unwrap(),
+///     "name=api\n\n[server]\nport=8080\nhosts=a, b\n",
+/// );
+/// ```
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, SerializeError> {
+	let Node::Table(entries) = value.serialize(NodeSerializer)? else {
+		return Err(SerializeError::new(
+			"expected a struct or map, found a value",
+		));
+	};
+	let mut prelude = vec![];
+	let mut sections = vec![];
+	for (key, node) in entries {
+		match node {
+			Node::Value(value) => prelude.push(Item::Pair(pair(key, value)?)),
+			Node::Table(entries) => {
+				if key.is_empty() || key.contains(['\r', '\n']) {
+					return Err(SerializeError::new(
+						"invalid section name, which must not be empty or contain a line break",
+					)
+					.in_key(&key));
+				}
+				let mut body = vec![];
+				for (inner, node) in entries {
+					match node {
+						Node::Value(value) => {
+							body.push(Item::Pair(pair(inner, value).map_err(|e| e.in_key(&key))?))
+						}
+						Node::Table(_) => {
+							return Err(SerializeError::new("sections cannot be nested")
+								.in_key(&inner)
+								.in_key(&key))
+						}
+						Node::Omitted => (),
+					}
+				}
+				sections.push(Item::Section(Section {
+					name: key,
+					body,
+					span: None,
+					name_span: None,
+				}));
+			}
+			Node::Omitted => (),
+		}
+	}
+	prelude.extend(sections);
+	Ok(OneINIAST::new(prelude).to_string())
+}
+
+fn pair(key: String, value: String) -> Result<Pair, SerializeError> {
+	Pair::check(&key, &value).map_err(|message| SerializeError::new(message).in_key(&key))?;
+	Ok(Pair {
+		key,
+		value,
+		span: None,
+		key_span: None,
+		value_span: None,
+	})
+}
+
+/// A serialized value, before it is checked to be representable.
+enum Node {
+	Value(String),
+	Table(Vec<(String, Node)>),
+	/// A `None`, which is left out.
+	Omitted,
+}
+
+struct NodeSerializer;
+
+impl NodeSerializer {
+	fn value<T: fmt::Display>(value: T) -> Result<Node, SerializeError> {
+		Ok(Node::Value(value.to_string()))
+	}
+}
+
+impl ser::Serializer for NodeSerializer {
+	type Ok = Node;
+	type Error = SerializeError;
+	type SerializeSeq = List;
+	type SerializeTuple = List;
+	type SerializeTupleStruct = List;
+	type SerializeTupleVariant = Impossible<Node, SerializeError>;
+	type SerializeMap = Table;
+	type SerializeStruct = Table;
+	type SerializeStructVariant = Impossible<Node, SerializeError>;
+
+	fn serialize_bool(self, v: bool) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_i128(self, v: i128) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_u128(self, v: u128) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_char(self, v: char) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Node, SerializeError> {
+		NodeSerializer::value(v)
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<Node, SerializeError> {
+		match std::str::from_utf8(v) {
+			Ok(v) => NodeSerializer::value(v),
+			Err(_) => Err(SerializeError::new("bytes are not valid UTF-8")),
+		}
+	}
+
+	fn serialize_none(self) -> Result<Node, SerializeError> {
+		Ok(Node::Omitted)
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Node, SerializeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Node, SerializeError> {
+		NodeSerializer::value("")
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, SerializeError> {
+		NodeSerializer::value("")
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Node, SerializeError> {
+		NodeSerializer::value(variant)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Node, SerializeError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		_value: &T,
+	) -> Result<Node, SerializeError> {
+		Err(variant_with_data(name, variant))
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<List, SerializeError> {
+		Ok(List { items: vec![] })
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<List, SerializeError> {
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		len: usize,
+	) -> Result<List, SerializeError> {
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, SerializeError> {
+		Err(variant_with_data(name, variant))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Table, SerializeError> {
+		Ok(Table {
+			entries: vec![],
+			key: None,
+		})
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Table, SerializeError> {
+		self.serialize_map(Some(len))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStructVariant, SerializeError> {
+		Err(variant_with_data(name, variant))
+	}
+}
+
+fn variant_with_data(name: &str, variant: &str) -> SerializeError {
+	SerializeError::new(format!(
+		"enum variant `{}::{}` has data, which INI cannot represent",
+		name, variant
+	))
+}
+
+/// The items of a sequence, which are joined with `, `.
+struct List {
+	items: Vec<String>,
+}
+
+impl ser::SerializeSeq for List {
+	type Ok = Node;
+	type Error = SerializeError;
+
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), SerializeError> {
+		match value.serialize(NodeSerializer)? {
+			Node::Value(item) if item.contains(',') || item.trim_matches([' ', '\t']) != item => {
+				Err(SerializeError::new(format!(
+					"list item `{}` contains `,` or starts or ends with whitespace",
+					item
+				)))
+			}
+			Node::Value(item) => {
+				self.items.push(item);
+				Ok(())
+			}
+			Node::Table(_) => Err(SerializeError::new("lists can only contain values")),
+			Node::Omitted => Err(SerializeError::new("lists cannot contain `None`")),
+		}
+	}
+
+	fn end(self) -> Result<Node, SerializeError> {
+		Ok(Node::Value(self.items.join(", ")))
+	}
+}
+
+impl ser::SerializeTuple for List {
+	type Ok = Node;
+	type Error = SerializeError;
+
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), SerializeError> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<Node, SerializeError> {
+		ser::SerializeSeq::end(self)
+	}
+}
+
+impl ser::SerializeTupleStruct for List {
+	type Ok = Node;
+	type Error = SerializeError;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<Node, SerializeError> {
+		ser::SerializeSeq::end(self)
+	}
+}
+
+/// The entries of a struct or map.
+struct Table {
+	entries: Vec<(String, Node)>,
+	/// The key of the entry whose value is next.
+	key: Option<String>,
+}
+
+impl Table {
+	fn entry<T: Serialize + ?Sized>(
+		&mut self,
+		key: String,
+		value: &T,
+	) -> Result<(), SerializeError> {
+		let node = value
+			.serialize(NodeSerializer)
+			.map_err(|e| e.in_key(&key))?;
+		self.entries.push((key, node));
+		Ok(())
+	}
+}
+
+impl ser::SerializeMap for Table {
+	type Ok = Node;
+	type Error = SerializeError;
+
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerializeError> {
+		match key.serialize(NodeSerializer)? {
+			Node::Value(key) => {
+				self.key = Some(key);
+				Ok(())
+			}
+			_ => Err(SerializeError::new("map keys must be values")),
+		}
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+		// Serde calls `serialize_key` first.
+		let key = self.key.take().unwrap();
+		self.entry(key, value)
+	}
+
+	fn end(self) -> Result<Node, SerializeError> {
+		Ok(Node::Table(self.entries))
+	}
+}
+
+impl ser::SerializeStruct for Table {
+	type Ok = Node;
+	type Error = SerializeError;
+
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), SerializeError> {
+		self.entry(String::from(key), value)
+	}
+
+	fn end(self) -> Result<Node, SerializeError> {
+		Ok(Node::Table(self.entries))
+	}
+}
@@ -0,0 +1,225 @@
+//! # Source spans
+//!
+//! Locations of [AST](crate::OneINIAST) nodes within the parsed contents.
+
+use crate::OffsetEncoding;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A location within the parsed contents.
+///
+/// # Example
+///
+/// ```
+/// let ast = one_ini::parse("a=b\r\n[é]\n").unwrap();
+/// let one_ini::Item::Section(section) = &ast.body[1] else { panic!() };
+/// let end = section.name_span.unwrap().end;
+///
+/// assert_eq!(end.offset, 8);
+/// assert_eq!((end.line, end.column), (2, 3));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+	/// The UTF-8 byte offset from the start of the contents.
+	pub offset: usize,
+	/// The one-based line number. `\n`, `\r\n` and `\r` all end a line.
+	pub line: usize,
+	/// The one-based column, counted in characters.
+	pub column: usize,
+}
+
+impl Position {
+	/// Converts the position into the given encoding, so that both its
+	/// offset and its column count units of the encoding, for the contents it
+	/// was parsed from. An offset past the end of the contents, or within a
+	/// character, is first moved back to the end or to the start of that
+	/// character.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use one_ini::OffsetEncoding;
+	///
+	/// let ast = one_ini::parse("[😀]\n😀 = b\n").unwrap();
+	/// let one_ini::Item::Section(section) = &ast.body[0] else { panic!() };
+	/// let one_ini::Item::Pair(pair) = &section.body[0] else { panic!() };
+	/// let end = pair.key_span.unwrap().end;
+	/// let utf16 = end.encoded("[😀]\n😀 = b\n", OffsetEncoding::Utf16);
+	///
+	/// assert_eq!((end.offset, end.column), (11, 2));
+	/// assert_eq!((utf16.offset, utf16.line, utf16.column), (7, 2, 3));
+	/// ```
+	pub fn encoded(&self, contents: &str, encoding: OffsetEncoding) -> Position {
+		let offset = clamp(contents, self.offset);
+		let line_start = contents[..offset].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+		Position {
+			offset: encoding.offset(contents, offset),
+			line: self.line,
+			column: encoding.offset(&contents[line_start..], offset - line_start) + 1,
+		}
+	}
+}
+
+/// The range of the contents a node was parsed from, excluding the
+/// surrounding whitespace and line separator.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+	/// The position of the first character.
+	pub start: Position,
+	/// The position just after the last character.
+	pub end: Position,
+}
+
+impl Span {
+	/// Converts both ends of the span into the given encoding, as
+	/// [Position::encoded] does.
+	pub fn encoded(&self, contents: &str, encoding: OffsetEncoding) -> Span {
+		Span {
+			start: self.start.encoded(contents, encoding),
+			end: self.end.encoded(contents, encoding),
+		}
+	}
+}
+
+/// A table of the line starts of some contents, built once to convert many
+/// byte offsets, such as those of [parse_to_vec](crate::parse_to_vec) tokens
+/// or of AST spans, into lines and columns and back. `\n`, `\r\n` and `\r`
+/// all end a line, as they do in the grammar.
+///
+/// [line_col](LineIndex::line_col) and [offset](LineIndex::offset) count
+/// lines and columns from zero, as editors and the Language Server Protocol
+/// do, with columns in the units of an [OffsetEncoding].
+/// [position](LineIndex::position) and [span](LineIndex::span) count from
+/// one, with columns in characters, as the spans of the AST do.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::{LineIndex, OffsetEncoding};
+///
+/// let contents = "[*]\r\nkey = 😀\rb = c\n";
+/// let tokens = one_ini::parse_to_vec(contents).unwrap();
+/// let index = LineIndex::new(contents);
+///
+/// // The `b` key, after the lone `\r`.
+/// let offset = tokens[10] as usize;
+/// assert_eq!(index.line_col(offset, OffsetEncoding::Utf8), (2, 0));
+/// assert_eq!(index.offset(2, 0, OffsetEncoding::Utf8), Some(offset));
+///
+/// // The end of the `😀` value.
+/// let end = tokens[8] as usize;
+/// assert_eq!(index.line_col(end, OffsetEncoding::Utf16), (1, 8));
+/// let position = index.position(end);
+/// assert_eq!((position.line, position.column), (2, 8));
+/// assert_eq!(
+///     index.offset(position.line - 1, position.column - 1, OffsetEncoding::CodePoint),
+///     Some(end),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+	contents: &'a str,
+	line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+	pub fn new(contents: &'a str) -> Self {
+		let bytes = contents.as_bytes();
+		let mut line_starts = vec![0];
+		for (i, &b) in bytes.iter().enumerate() {
+			let ends_line = b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n'));
+			if ends_line {
+				line_starts.push(i + 1);
+			}
+		}
+		LineIndex {
+			contents,
+			line_starts,
+		}
+	}
+
+	/// The number of lines, which is one more than the number of line breaks.
+	pub fn line_count(&self) -> usize {
+		self.line_starts.len()
+	}
+
+	/// The byte range of a zero-based line, without its line break, or `None`
+	/// past the last line.
+	pub fn line(&self, line: usize) -> Option<Range<usize>> {
+		let start = *self.line_starts.get(line)?;
+		let end = match self.line_starts.get(line + 1) {
+			Some(&next) => match self.contents[..next].ends_with("\r\n") {
+				true => next - 2,
+				false => next - 1,
+			},
+			None => self.contents.len(),
+		};
+		Some(start..end)
+	}
+
+	/// The zero-based line and column of a byte offset, with the column
+	/// counted in units of the encoding.
+	///
+	/// An offset past the end of the contents is clamped to the end, and an
+	/// offset within a character moves to the start of the character.
+	pub fn line_col(&self, offset: usize, encoding: OffsetEncoding) -> (usize, usize) {
+		let offset = self.clamp(offset);
+		let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+		let line_start = self.line_starts[line];
+		let column = encoding.offset(&self.contents[line_start..], offset - line_start);
+		(line, column)
+	}
+
+	/// The byte offset of a zero-based line and column, with the column
+	/// counted in units of the encoding, or `None` past the last line.
+	///
+	/// A column past the end of the line is clamped to the end, before the
+	/// line break, and a column within a character moves to the end of the
+	/// character.
+	pub fn offset(&self, line: usize, column: usize, encoding: OffsetEncoding) -> Option<usize> {
+		let range = self.line(line)?;
+		let mut units = 0;
+		for (i, c) in self.contents[range.clone()].char_indices() {
+			if units >= column {
+				return Some(range.start + i);
+			}
+			units += encoding.char_len(c);
+		}
+		Some(range.end)
+	}
+
+	/// The one-based position of a byte offset, with the column counted in
+	/// characters, as in the spans of the AST. The offset is clamped as in
+	/// [line_col](LineIndex::line_col).
+	pub fn position(&self, offset: usize) -> Position {
+		let offset = self.clamp(offset);
+		let (line, column) = self.line_col(offset, OffsetEncoding::CodePoint);
+		Position {
+			offset,
+			line: line + 1,
+			column: column + 1,
+		}
+	}
+
+	/// The span between two byte offsets, as in the AST.
+	pub fn span(&self, start: usize, end: usize) -> Span {
+		Span {
+			start: self.position(start),
+			end: self.position(end),
+		}
+	}
+
+	fn clamp(&self, offset: usize) -> usize {
+		clamp(self.contents, offset)
+	}
+}
+
+/// The offset, moved back to the end of the contents or to the start of the
+/// character it is within.
+pub(crate) fn clamp(contents: &str, offset: usize) -> usize {
+	let mut offset = offset.min(contents.len());
+	while !contents.is_char_boundary(offset) {
+		offset -= 1;
+	}
+	offset
+}
@@ -0,0 +1,192 @@
+//! # Tokens
+//!
+//! Token streams for Rust callers and for syntax highlighters: a lazy
+//! iterator of borrowed tokens, and a stream that covers every byte of the
+//! contents, with the brackets, `=` signs, whitespace and line breaks that
+//! [parse_to_vec](crate::parse_to_vec) leaves out.
+
+use crate::lexer::{Lexeme, Lexer};
+use crate::{parse_recovering, Item, TokenTypes};
+use std::iter;
+use std::ops::Range;
+
+/// Tokenizes
+/// [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+/// contents into the same form of array as [parse_to_vec](crate::parse_to_vec),
+/// adding trivia tokens so that the tokens cover every byte of the contents,
+/// in order and without overlapping.
+///
+/// Besides the tokens of `parse_to_vec`, the array has `SectionOpen` and
+/// `SectionClose` tokens for the brackets of section headers, `Equals`
+/// tokens, `Whitespace` tokens for runs of spaces and tabs, `Newline` tokens
+/// for line breaks, and a `Bom` token for a leading byte order mark. Lines
+/// that cannot be parsed become `Invalid` tokens rather than errors, as
+/// [parse_recovering] keeps them.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::TokenTypes::*;
+///
+/// let contents = "[*]\na = b\n";
+/// let tokens = one_ini::parse_to_vec_with_trivia(contents);
+/// let kinds: Vec<u32> = tokens.chunks(3).map(|token| token[0]).collect();
+///
+/// assert_eq!(
+///     kinds,
+///     [SectionOpen, Section, SectionClose, Newline, Key, Whitespace, Equals, Whitespace, Value, Newline]
+///         .map(|kind| kind as u32),
+/// );
+/// assert_eq!(tokens[tokens.len() - 1] as usize, contents.len());
+/// ```
+pub fn parse_to_vec_with_trivia(contents: &str) -> Vec<u32> {
+	let (ast, _) = parse_recovering(contents);
+	let mut tokens = vec![];
+	significant_tokens(contents, &ast.body, &mut tokens);
+	let mut results = Vec::with_capacity(tokens.len() * 6);
+	let mut offset = 0;
+	if contents.starts_with('\u{feff}') {
+		offset = '\u{feff}'.len_utf8();
+		push(TokenTypes::Bom, 0, offset, &mut results);
+	}
+	for (kind, start, end) in tokens {
+		trivia(contents, offset, start, &mut results);
+		push(kind, start, end, &mut results);
+		offset = end;
+	}
+	trivia(contents, offset, contents.len(), &mut results);
+	results
+}
+
+fn push(kind: TokenTypes, start: usize, end: usize, results: &mut Vec<u32>) {
+	results.push(kind as u32);
+	results.push(start as u32);
+	results.push(end as u32);
+}
+
+/// Collects the tokens of the items, in order.
+fn significant_tokens(
+	contents: &str,
+	items: &[Item],
+	tokens: &mut Vec<(TokenTypes, usize, usize)>,
+) {
+	for item in items {
+		match item {
+			Item::Pair(pair) => {
+				let (key, value) = (pair.key_span.unwrap(), pair.value_span.unwrap());
+				// The `=` is the first one after the key, which cannot
+				// contain one.
+				let equals = key.end.offset
+					+ contents[key.end.offset..value.start.offset]
+						.find('=')
+						.unwrap();
+				tokens.push((TokenTypes::Key, key.start.offset, key.end.offset));
+				tokens.push((TokenTypes::Equals, equals, equals + 1));
+				tokens.push((TokenTypes::Value, value.start.offset, value.end.offset));
+			}
+			Item::Section(section) => {
+				let name = section.name_span.unwrap();
+				let (start, end) = (name.start.offset, name.end.offset);
+				tokens.push((TokenTypes::SectionOpen, start - 1, start));
+				tokens.push((TokenTypes::Section, start, end));
+				tokens.push((TokenTypes::SectionClose, end, end + 1));
+				significant_tokens(contents, &section.body, tokens);
+			}
+			Item::Comment(comment) => {
+				let span = comment.span.unwrap();
+				let indicator_end = span.start.offset + comment.indicator.len_utf8();
+				tokens.push((
+					TokenTypes::CommentIndicator,
+					span.start.offset,
+					indicator_end,
+				));
+				tokens.push((TokenTypes::CommentValue, indicator_end, span.end.offset));
+			}
+			Item::Error(invalid) => {
+				let span = invalid.span.unwrap();
+				tokens.push((TokenTypes::Invalid, span.start.offset, span.end.offset));
+			}
+		}
+	}
+}
+
+/// Tokenizes the whitespace and line breaks between two tokens. Anything
+/// else becomes an `Invalid` token, although the parser leaves nothing else
+/// between tokens.
+fn trivia(contents: &str, start: usize, end: usize, results: &mut Vec<u32>) {
+	let bytes = &contents.as_bytes()[..end];
+	let run = |i: usize, in_run: fn(&u8) -> bool| {
+		i + bytes[i..].iter().take_while(|&b| in_run(b)).count()
+	};
+	let mut i = start;
+	while i < end {
+		let (kind, next) = match bytes[i] {
+			b'\r' if bytes.get(i + 1) == Some(&b'\n') => (TokenTypes::Newline, i + 2),
+			b'\r' | b'\n' => (TokenTypes::Newline, i + 1),
+			b' ' | b'\t' => (TokenTypes::Whitespace, run(i, |&b| b == b' ' || b == b'\t')),
+			_ => (
+				TokenTypes::Invalid,
+				run(i, |&b| !matches!(b, b' ' | b'\t' | b'\r' | b'\n')),
+			),
+		};
+		push(kind, i, next, results);
+		i = next;
+	}
+}
+
+/// A token of [tokenize], borrowing its text from the contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+	pub kind: TokenTypes,
+	/// The byte offsets of the token in the contents.
+	pub span: Range<usize>,
+	/// The text of the token, which is `&contents[span]`.
+	pub text: &'a str,
+}
+
+/// Tokenizes
+/// [EditorConfig-INI](https://editorconfig-specification.readthedocs.io/en/latest/#file-format)
+/// contents lazily, one line at a time and without allocating, into the
+/// tokens of [parse_to_vec](crate::parse_to_vec).
+///
+/// As the iterator cannot fail, a line that cannot be parsed becomes a single
+/// `Invalid` token, without its surrounding whitespace, and tokenizing goes on
+/// with the next line. Contents that `parse_to_vec` accepts have no `Invalid`
+/// tokens.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::TokenTypes;
+///
+/// let mut tokens = one_ini::tokenize("[*.md]\nindent_size = 2\n");
+/// let section = tokens.next().unwrap();
+/// assert_eq!(section.kind, TokenTypes::Section);
+/// assert_eq!(section.span, 1..5);
+/// assert_eq!(section.text, "*.md");
+///
+/// let texts: Vec<&str> = tokens.map(|token| token.text).collect();
+/// assert_eq!(texts, ["indent_size", "2"]);
+/// ```
+pub fn tokenize(contents: &str) -> impl Iterator<Item = Token<'_>> {
+	Lexer::new(contents).flat_map(move |lexeme| {
+		let token = |kind, span: Range<usize>| Token {
+			kind,
+			text: &contents[span.clone()],
+			span,
+		};
+		let (first, second) = match lexeme {
+			Lexeme::Section { name } => (token(TokenTypes::Section, name), None),
+			Lexeme::Pair { key, value } => (
+				token(TokenTypes::Key, key),
+				Some(token(TokenTypes::Value, value)),
+			),
+			Lexeme::Comment { indicator, value } => (
+				token(TokenTypes::CommentIndicator, indicator..indicator + 1),
+				Some(token(TokenTypes::CommentValue, value)),
+			),
+			Lexeme::Invalid { text } => (token(TokenTypes::Invalid, text), None),
+		};
+		iter::once(first).chain(second)
+	})
+}
@@ -0,0 +1,85 @@
+//! # Versions
+//!
+//! EditorConfig core versions, which select between the behaviours of older
+//! and newer cores when resolving properties.
+
+use std::{error, fmt, str};
+
+/// An EditorConfig core version, such as `0.9.0`.
+///
+/// # Example
+///
+/// ```
+/// use one_ini::Version;
+///
+/// let version: Version = "0.9".parse().unwrap();
+///
+/// assert_eq!(version, Version::new(0, 9, 0));
+/// assert!(version < Version::CURRENT);
+/// assert_eq!(version.to_string(), "0.9.0");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+	pub major: u32,
+	pub minor: u32,
+	pub patch: u32,
+}
+
+impl Version {
+	/// The version whose behaviour is used unless another is requested.
+	pub const CURRENT: Version = Version::new(0, 17, 2);
+
+	pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+		Version {
+			major,
+			minor,
+			patch,
+		}
+	}
+}
+
+impl Default for Version {
+	fn default() -> Self {
+		Version::CURRENT
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "{}.{}.{}", self.major, self.minor, self.patch)
+	}
+}
+
+/// An error raised for a version that is not one to three `.`-separated
+/// numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseVersionError {
+	version: String,
+}
+
+impl fmt::Display for ParseVersionError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "invalid version `{}`", self.version)
+	}
+}
+
+impl error::Error for ParseVersionError {}
+
+impl str::FromStr for Version {
+	type Err = ParseVersionError;
+
+	/// Parses `major[.minor[.patch]]`, where missing parts are zero.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = || ParseVersionError {
+			version: String::from(s),
+		};
+		let mut parts = [0; 3];
+		for (i, part) in s.split('.').enumerate() {
+			if i == parts.len() || part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+				return Err(error());
+			}
+			parts[i] = part.parse().map_err(|_| error())?;
+		}
+		Ok(Version::new(parts[0], parts[1], parts[2]))
+	}
+}
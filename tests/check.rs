@@ -0,0 +1,242 @@
+//! Integration test suite for checking files against their properties.
+
+use one_ini::{check, check_tree, Properties, Violation, ViolationCode};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn conforming_contents_have_no_violations() {
+	let properties = Properties::from_iter([
+		("indent_style", "space"),
+		("indent_size", "2"),
+		("end_of_line", "lf"),
+		("charset", "utf-8"),
+		("trim_trailing_whitespace", "true"),
+		("insert_final_newline", "true"),
+		("max_line_length", "12"),
+	]);
+	let contents = "fn main() {\n  /**\n   * x\n   */\n\n  ok();\n}\n";
+	assert_eq!(check(contents.as_bytes(), &properties), []);
+}
+
+#[test]
+fn unset_properties_are_not_checked() {
+	let contents = b"\xef\xbb\xbf\t  a \r\n  b";
+	assert_eq!(check(contents, &Properties::new()), []);
+	let properties = Properties::from_iter([("end_of_line", "unset"), ("max_line_length", "off")]);
+	assert_eq!(check(contents, &properties), []);
+}
+
+#[test]
+fn end_of_line() {
+	let properties = Properties::from_iter([("end_of_line", "crlf")]);
+	let violations = check(b"a\r\nb\nc\rd", &properties);
+	assert_eq!(
+		locations(&violations),
+		[
+			(ViolationCode::EndOfLine, 2, 2),
+			(ViolationCode::EndOfLine, 3, 2)
+		]
+	);
+	assert_eq!(
+		violations[1].message,
+		"line ends with `cr`, expected `crlf`"
+	);
+}
+
+#[test]
+fn tabs_where_spaces_are_expected() {
+	let properties = Properties::from_iter([("indent_style", "space")]);
+	let violations = check(b"a\n  \tb\n\n\t\n", &properties);
+	assert_eq!(locations(&violations), [(ViolationCode::IndentStyle, 2, 3)]);
+	assert_eq!(
+		violations[0].message,
+		"indented with a tab, expected spaces"
+	);
+}
+
+#[test]
+fn spaces_where_tabs_are_expected() {
+	let properties = Properties::from_iter([("indent_style", "tab")]);
+	let violations = check(b"\ta\n\t  * aligned\n  b\n\t \tc\n", &properties);
+	assert_eq!(
+		locations(&violations),
+		[
+			(ViolationCode::IndentStyle, 3, 1),
+			(ViolationCode::IndentStyle, 4, 2)
+		]
+	);
+}
+
+#[test]
+fn spaces_within_a_tab_stop_align() {
+	let properties = Properties::from_iter([("indent_style", "tab"), ("indent_size", "4")]);
+	let violations = check(b"   a\n\t   b\n    c\n\t    d\n", &properties);
+	assert_eq!(
+		locations(&violations),
+		[
+			(ViolationCode::IndentStyle, 3, 1),
+			(ViolationCode::IndentStyle, 4, 2)
+		]
+	);
+}
+
+#[test]
+fn indent_size() {
+	let properties = Properties::from_iter([("indent_style", "space"), ("indent_size", "4")]);
+	let violations = check(b"a\n    b\n      c\n     * d\n", &properties);
+	assert_eq!(locations(&violations), [(ViolationCode::IndentSize, 3, 7)]);
+	assert_eq!(
+		violations[0].message,
+		"indented by 6 spaces, expected a multiple of 4"
+	);
+}
+
+#[test]
+fn trailing_whitespace() {
+	let properties = Properties::from_iter([("trim_trailing_whitespace", "true")]);
+	let violations = check("é \t\r\n  \nb\n".as_bytes(), &properties);
+	assert_eq!(
+		locations(&violations),
+		[
+			(ViolationCode::TrailingWhitespace, 1, 2),
+			(ViolationCode::TrailingWhitespace, 2, 1),
+		]
+	);
+	let properties = Properties::from_iter([("trim_trailing_whitespace", "false")]);
+	assert_eq!(check(b"a \n", &properties), []);
+}
+
+#[test]
+fn final_newline() {
+	let properties = Properties::from_iter([("insert_final_newline", "true")]);
+	assert_eq!(
+		locations(&check(b"a\nb", &properties)),
+		[(ViolationCode::FinalNewline, 2, 2)]
+	);
+	assert_eq!(check(b"a\nb\n", &properties), []);
+	assert_eq!(check(b"", &properties), []);
+	let properties = Properties::from_iter([("insert_final_newline", "false")]);
+	let violations = check(b"a\nb\r\n", &properties);
+	assert_eq!(
+		locations(&violations),
+		[(ViolationCode::FinalNewline, 2, 2)]
+	);
+	assert_eq!(violations[0].message, "final newline, expected none");
+}
+
+#[test]
+fn max_line_length() {
+	let properties = Properties::from_iter([("max_line_length", "4")]);
+	let violations = check("abcd\nabcdé\n".as_bytes(), &properties);
+	assert_eq!(
+		locations(&violations),
+		[(ViolationCode::MaxLineLength, 2, 5)]
+	);
+	assert_eq!(
+		violations[0].message,
+		"line is 5 columns long, more than the `max_line_length` of 4"
+	);
+	let properties = Properties::from_iter([("max_line_length", "4"), ("tab_width", "4")]);
+	let violations = check(b"a\tb\n", &properties);
+	assert_eq!(
+		locations(&violations),
+		[(ViolationCode::MaxLineLength, 1, 3)]
+	);
+}
+
+#[test]
+fn charset() {
+	let bom = b"\xef\xbb\xbfa\n";
+	let utf8 = Properties::from_iter([("charset", "utf-8")]);
+	let utf8_bom = Properties::from_iter([("charset", "utf-8-bom")]);
+	let latin1 = Properties::from_iter([("charset", "latin1")]);
+	assert_eq!(
+		messages(&check(bom, &utf8)),
+		["byte order mark, expected `utf-8` without one"]
+	);
+	assert_eq!(check(bom, &utf8_bom), []);
+	assert_eq!(
+		messages(&check(b"a\n", &utf8_bom)),
+		["missing byte order mark, expected `utf-8-bom`"]
+	);
+	assert_eq!(
+		messages(&check(bom, &latin1)),
+		["UTF-8 byte order mark, expected `latin1`"]
+	);
+	assert_eq!(check(b"caf\xe9\n", &latin1), []);
+	let violations = check(b"a\ncaf\xe9\n", &utf8);
+	assert_eq!(locations(&violations), [(ViolationCode::Charset, 2, 4)]);
+	assert_eq!(violations[0].message, "invalid UTF-8, expected `utf-8`");
+}
+
+#[test]
+fn utf16_is_only_checked_for_its_byte_order_mark() {
+	let properties = Properties::from_iter([
+		("charset", "utf-16le"),
+		("trim_trailing_whitespace", "true"),
+	]);
+	assert_eq!(check(b"\xff\xfea\0 \0\n\0", &properties), []);
+	assert_eq!(
+		messages(&check(b"\xfe\xffa\0", &properties)),
+		["missing byte order mark, expected `utf-16le`"]
+	);
+}
+
+#[test]
+fn columns_start_after_a_byte_order_mark() {
+	let properties = Properties::from_iter([("trim_trailing_whitespace", "true")]);
+	let violations = check(b"\xef\xbb\xbfa \n", &properties);
+	assert_eq!(
+		locations(&violations),
+		[(ViolationCode::TrailingWhitespace, 1, 2)]
+	);
+	assert_eq!(violations[0].position.offset, 4);
+}
+
+#[test]
+fn checks_directory_trees() {
+	let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("check_tree");
+	let _ = fs::remove_dir_all(&root);
+	fs::create_dir_all(root.join("src")).unwrap();
+	fs::create_dir_all(root.join(".git")).unwrap();
+	fs::write(
+		root.join(".editorconfig"),
+		"root = true\n[*]\ninsert_final_newline = true\n[*.md]\ntrim_trailing_whitespace = true\n",
+	)
+	.unwrap();
+	fs::write(root.join("README.md"), "# Title \n").unwrap();
+	fs::write(root.join("src/ok.rs"), "fn main() {}\n").unwrap();
+	fs::write(root.join("src/bad.rs"), "fn main() {}").unwrap();
+	fs::write(root.join("src/image.png"), b"\x89PNG\0").unwrap();
+	fs::write(root.join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+	let results = check_tree(&root, ".editorconfig").unwrap();
+	let summary: Vec<(&Path, Vec<ViolationCode>)> = results
+		.iter()
+		.map(|(path, violations)| {
+			let path = path.strip_prefix(&root).unwrap();
+			(path, violations.iter().map(|v| v.code).collect())
+		})
+		.collect();
+	assert_eq!(
+		summary,
+		[
+			(
+				Path::new("README.md"),
+				vec![ViolationCode::TrailingWhitespace]
+			),
+			(Path::new("src/bad.rs"), vec![ViolationCode::FinalNewline]),
+		]
+	);
+}
+
+fn locations(violations: &[Violation]) -> Vec<(ViolationCode, usize, usize)> {
+	violations
+		.iter()
+		.map(|v| (v.code, v.position.line, v.position.column))
+		.collect()
+}
+
+fn messages(violations: &[Violation]) -> Vec<&str> {
+	violations.iter().map(|v| v.message.as_str()).collect()
+}
@@ -0,0 +1,231 @@
+//! Integration test suite for the `one-ini` command-line tool.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+#[test]
+fn parse_prints_json() {
+	let output = run(&["parse"], "root=true\n");
+	assert!(output.status.success());
+	let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(json["body"][0]["type"], "Pair");
+	assert_eq!(json["body"][0]["key"], "root");
+}
+
+#[test]
+fn parse_reports_errors() {
+	let output = run(&["parse", "-"], "[*\n");
+	assert_eq!(output.status.code(), Some(1));
+	assert_eq!(
+		stderr(&output),
+		"one-ini: <stdin>: section header must end with `]` at line 1, column 1\n"
+	);
+}
+
+#[test]
+fn tokens_prints_one_token_per_line() {
+	let output = run(&["tokens"], "a=b\n[c]\n#d\n");
+	assert_eq!(
+		stdout(&output),
+		"Key              0..1 \"a\"\n\
+		 Value            2..3 \"b\"\n\
+		 Section          5..6 \"c\"\n\
+		 CommentIndicator 8..9 \"#\"\n\
+		 CommentValue     9..10 \"d\"\n"
+	);
+}
+
+#[test]
+fn tokens_prints_trivia() {
+	let output = run(&["tokens", "--trivia"], "a = b\n[c\n");
+	assert_eq!(
+		stdout(&output),
+		"Key              0..1 \"a\"\n\
+		 Whitespace       1..2 \" \"\n\
+		 Equals           2..3 \"=\"\n\
+		 Whitespace       3..4 \" \"\n\
+		 Value            4..5 \"b\"\n\
+		 Newline          5..6 \"\\n\"\n\
+		 Invalid          6..8 \"[c\"\n\
+		 Newline          8..9 \"\\n\"\n"
+	);
+}
+
+#[test]
+fn fmt_prints_formatted_contents() {
+	let output = run(
+		&["fmt", "--spaces-around-equals", "--end-of-line", "crlf"],
+		"[*]\nindent_style=tab\n",
+	);
+	assert!(output.status.success());
+	assert_eq!(stdout(&output), "[*]\r\nindent_style = tab\r\n");
+}
+
+#[test]
+fn fmt_check_fails_on_unformatted_contents() {
+	let output = run(&["fmt", "--check"], "a = b\n");
+	assert_eq!(output.status.code(), Some(1));
+	assert_eq!(stdout(&output), "<stdin>\n");
+	let output = run(&["fmt", "--check"], "a=b\n");
+	assert!(output.status.success());
+	assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn fmt_write_rewrites_files() {
+	let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fmt_write.editorconfig");
+	fs::write(&path, "[*]\n  a = b\n").unwrap();
+	let output = run(&["fmt", "--write", path.to_str().unwrap()], "");
+	assert!(output.status.success());
+	assert_eq!(fs::read_to_string(&path).unwrap(), "[*]\na=b\n");
+}
+
+#[test]
+fn lint_reports_locations() {
+	let output = run(&["lint"], "[*]\nindent_style = tabz\nquote_type = single\n");
+	assert_eq!(output.status.code(), Some(1));
+	assert_eq!(
+		stdout(&output),
+		"<stdin>:2:16: error[invalid-value]: `tabz` is not a valid `indent_style`, expected `tab` or `space`\n\
+		 <stdin>:3:1: info[unknown-property]: `quote_type` is not a supported property\n"
+	);
+	let output = run(&["lint"], "[*]\nquote_type = single\n");
+	assert!(output.status.success());
+}
+
+#[test]
+fn resolve_prints_properties() {
+	let output = run(&["resolve", &fixture("nested/src/main.rs")], "");
+	assert!(output.status.success());
+	assert_eq!(
+		stdout(&output),
+		"indent_style=space\nindent_size=4\ntab_width=4\n"
+	);
+}
+
+#[test]
+fn resolve_prints_headers_for_several_files() {
+	let first = fixture("a.txt");
+	let second = fixture("nested/root/b.txt");
+	let output = run(&["resolve", &first, &second], "");
+	assert_eq!(
+		stdout(&output),
+		format!(
+			"[{}]\nindent_style=space\nindent_size=2\ntab_width=2\n[{}]\nend_of_line=lf\n",
+			first, second
+		)
+	);
+}
+
+#[test]
+fn resolve_with_config_file_name_and_version() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("resolve_options");
+	fs::create_dir_all(&dir).unwrap();
+	fs::write(dir.join("custom.ini"), "root=true\n[*]\nindent_style=tab\n").unwrap();
+	let file = dir.join("a.c");
+	let file = file.to_str().unwrap();
+	let output = run(&["resolve", "-f", "custom.ini", file], "");
+	assert_eq!(stdout(&output), "indent_style=tab\nindent_size=tab\n");
+	let output = run(&["resolve", "-f", "custom.ini", "-b", "0.9.0", file], "");
+	assert_eq!(stdout(&output), "indent_style=tab\n");
+}
+
+#[test]
+fn check_reports_violations() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("check");
+	fs::create_dir_all(&dir).unwrap();
+	fs::write(
+		dir.join(".editorconfig"),
+		"root=true\n[*.c]\nend_of_line=lf\n",
+	)
+	.unwrap();
+	fs::write(dir.join("a.c"), "int a;\r\n").unwrap();
+	let output = run(&["check", dir.to_str().unwrap()], "");
+	assert_eq!(output.status.code(), Some(1));
+	assert_eq!(
+		stdout(&output),
+		format!(
+			"{}:1:7: end-of-line: line ends with `crlf`, expected `lf`\n",
+			dir.join("a.c").display()
+		)
+	);
+	fs::write(dir.join("a.c"), "int a;\n").unwrap();
+	let output = run(&["check", dir.to_str().unwrap()], "");
+	assert!(output.status.success());
+	assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn check_fix_rewrites_files() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("check_fix");
+	fs::create_dir_all(&dir).unwrap();
+	fs::write(
+		dir.join(".editorconfig"),
+		"root=true\n[*.c]\nend_of_line=lf\nmax_line_length=4\n",
+	)
+	.unwrap();
+	fs::write(dir.join("a.c"), "a;\r\nlong;\r\n").unwrap();
+	let output = run(&["check", "--fix", dir.to_str().unwrap()], "");
+	assert_eq!(output.status.code(), Some(1));
+	assert_eq!(
+		stdout(&output),
+		format!(
+			"{}:2:5: max-line-length: line is 5 columns long, more than the `max_line_length` of 4\n",
+			dir.join("a.c").display()
+		)
+	);
+	assert_eq!(fs::read_to_string(dir.join("a.c")).unwrap(), "a;\nlong;\n");
+}
+
+#[test]
+fn usage_errors() {
+	for args in [
+		&[][..],
+		&["frobnicate"],
+		&["resolve"],
+		&["resolve", "-b", "x", "a"],
+		&["resolve", "-b", "99.0.0", "a"],
+		&["fmt", "--bogus"],
+		&["check", "-"],
+	] {
+		let output = run(args, "");
+		assert_eq!(output.status.code(), Some(2), "{:?}", args);
+		assert!(stderr(&output).contains("Usage: one-ini"), "{:?}", args);
+	}
+}
+
+fn fixture(path: &str) -> String {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/fixtures/lookup")
+		.join(path)
+		.to_str()
+		.unwrap()
+		.to_string()
+}
+
+fn run(args: &[&str], stdin: &str) -> Output {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_one-ini"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(stdin.as_bytes())
+		.unwrap();
+	child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+	String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+	String::from_utf8(output.stderr.clone()).unwrap()
+}
@@ -0,0 +1,209 @@
+//! Integration test suite for deserializing INI contents with serde.
+
+use one_ini::{from_str, DeserializeError, ParseErrorKind};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+	host: String,
+	port: u16,
+	#[serde(default)]
+	debug: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+	Fast,
+	SafeAndSlow,
+}
+
+#[test]
+fn prelude_to_fields_and_sections_to_structs() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Config {
+		name: String,
+		retries: i32,
+		ratio: f64,
+		mode: Mode,
+		server: Server,
+	}
+
+	let config: Config = from_str(
+		"; service\nname = api\nretries = -3\nratio = 0.5\nmode = safe_and_slow\n\n\
+		 [server]\nhost = localhost\nport = 8080\ndebug = True\n",
+	)
+	.unwrap();
+	assert_eq!(
+		config,
+		Config {
+			name: String::from("api"),
+			retries: -3,
+			ratio: 0.5,
+			mode: Mode::SafeAndSlow,
+			server: Server {
+				host: String::from("localhost"),
+				port: 8080,
+				debug: true,
+			},
+		}
+	);
+}
+
+#[test]
+fn sections_to_maps() {
+	let servers: BTreeMap<String, Server> =
+		from_str("[a]\nhost = x\nport = 1\n[b]\nhost = y\nport = 2\n").unwrap();
+	assert_eq!(servers.keys().collect::<Vec<_>>(), ["a", "b"]);
+	assert_eq!(servers["b"].port, 2);
+
+	let sections: HashMap<String, HashMap<String, String>> =
+		from_str("[*.md]\nindent_size = 2\n").unwrap();
+	assert_eq!(sections["*.md"]["indent_size"], "2");
+}
+
+#[test]
+fn borrows_strings() {
+	#[derive(Deserialize)]
+	struct Config<'a> {
+		name: &'a str,
+	}
+
+	let contents = String::from("name = borrowed\n");
+	let config: Config = from_str(&contents).unwrap();
+	assert_eq!(config.name, "borrowed");
+}
+
+#[test]
+fn options_and_defaults() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Config {
+		present: Option<u8>,
+		missing: Option<u8>,
+		server: Option<Server>,
+	}
+
+	let config: Config = from_str("present = 1\n").unwrap();
+	assert_eq!(
+		config,
+		Config {
+			present: Some(1),
+			missing: None,
+			server: None,
+		}
+	);
+}
+
+#[test]
+fn empty_values_are_none() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Config {
+		port: Option<u16>,
+		name: Option<String>,
+	}
+
+	let config: Config = from_str(
+		"port =
+name=
+",
+	)
+	.unwrap();
+	assert_eq!(
+		config,
+		Config {
+			port: None,
+			name: None,
+		}
+	);
+}
+
+#[test]
+fn comma_separated_sequences() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Config {
+		ports: Vec<u16>,
+		pair: (char, bool),
+		empty: Vec<String>,
+	}
+
+	let config: Config = from_str("ports = 80, 443 ,8080\npair = x,false\nempty =\n").unwrap();
+	assert_eq!(
+		config,
+		Config {
+			ports: vec![80, 443, 8080],
+			pair: ('x', false),
+			empty: vec![],
+		}
+	);
+}
+
+#[test]
+fn later_keys_and_repeated_sections_override() {
+	let servers: HashMap<String, Server> =
+		from_str("[a]\nhost = x\nport = 1\n[a]\nport = 2\nport = 3\n").unwrap();
+	assert_eq!(servers["a"].host, "x");
+	assert_eq!(servers["a"].port, 3);
+}
+
+#[test]
+fn invalid_values_are_located() {
+	let error = from_str::<Server>("host = x\nport = eighty\n").unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"`eighty` is not a valid u16 at line 2, column 8"
+	);
+
+	let error = from_str::<Server>("host = x\nport = 1\ndebug = yes\n").unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"`yes` is not a valid bool, expected `true` or `false` at line 3, column 9"
+	);
+
+	#[derive(Deserialize, Debug)]
+	#[allow(dead_code)]
+	struct Config {
+		mode: Mode,
+	}
+
+	let error = from_str::<Config>("mode = slow\n").unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"unknown variant `slow`, expected `fast` or `safe_and_slow` at line 1, column 8"
+	);
+}
+
+#[test]
+fn missing_fields_in_sections_are_located_at_the_header() {
+	let error = from_str::<HashMap<String, Server>>("\n[a]\nhost = x\n").unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"missing field `port` at line 2, column 2"
+	);
+	let error = from_str::<Server>("host = x\n").unwrap_err();
+	assert_eq!(error.to_string(), "missing field `port`");
+}
+
+#[test]
+fn sections_where_values_are_expected() {
+	#[derive(Deserialize, Debug)]
+	#[allow(dead_code)]
+	struct Config {
+		server: String,
+	}
+
+	let error = from_str::<Config>("[server]\nhost = x\n").unwrap_err();
+	assert_eq!(
+		error.to_string(),
+		"invalid type: map, expected a string at line 1, column 2"
+	);
+}
+
+#[test]
+fn parse_errors() {
+	let error = from_str::<HashMap<String, String>>("[a\n").unwrap_err();
+	assert!(matches!(
+		error,
+		DeserializeError::Parse(ref e) if e.kind == ParseErrorKind::UnterminatedSectionHeader
+	));
+}
@@ -0,0 +1,260 @@
+//! Integration test suite for lossless documents.
+
+use one_ini::{parse_document, Item, LineContent};
+use std::fs;
+
+#[test]
+fn round_trips_fixture() {
+	let contents = fs::read_to_string("tests/fixtures/config.ini").unwrap();
+	round_trip(&contents);
+}
+
+#[test]
+fn keeps_spaces_around_equals() {
+	round_trip("a = b");
+	round_trip("a  =   b\n");
+	round_trip("a\t=\tb\n");
+}
+
+#[test]
+fn keeps_spaces_around_pairs() {
+	round_trip("  a=b  \n");
+	round_trip("a=b\n  c=d\ne=f");
+}
+
+#[test]
+fn keeps_blank_lines() {
+	round_trip("\na=b\n\nc=d");
+	round_trip("a=b\n\n\n[a]\n\n");
+	round_trip("a=b\n   \n\t\n");
+	round_trip("a=b\n   ");
+}
+
+#[test]
+fn keeps_spaces_around_section_headers() {
+	round_trip("[ a b ]\n");
+	round_trip("  [a]  \n");
+	round_trip("[[a]]\n");
+}
+
+#[test]
+fn keeps_comments() {
+	round_trip("# a  \n  ; b\n[a]\n#c\nd=e; f\n");
+}
+
+#[test]
+fn keeps_bom() {
+	let document = round_trip("\u{feff}a=b\n");
+	assert!(document.bom);
+}
+
+#[test]
+fn keeps_line_separators() {
+	round_trip("[a]\r\nb=c\r\n");
+	round_trip("[a]\rb=c\r\r\n\n");
+	round_trip("a=b\r\n  \r\n");
+}
+
+#[test]
+fn empty() {
+	let document = round_trip("");
+	assert!(document.prelude.is_empty());
+	assert!(document.sections.is_empty());
+}
+
+#[test]
+fn splits_lines() {
+	let document = parse_document("a = b \n\n[c]\t\r\n  # d\n").unwrap();
+	assert_eq!(document.prelude.len(), 2);
+	assert_eq!(
+		document.prelude[0].content,
+		LineContent::Pair {
+			key: String::from("a"),
+			before_equals: String::from(" "),
+			after_equals: String::from(" "),
+			value: String::from("b"),
+		}
+	);
+	assert_eq!(document.prelude[0].trailing, " ");
+	assert_eq!(document.prelude[1].content, LineContent::Blank);
+	let section = &document.sections[0];
+	assert_eq!(
+		section.header.content,
+		LineContent::Header {
+			name: String::from("c")
+		}
+	);
+	assert_eq!(section.header.trailing, "\t");
+	assert_eq!(section.header.newline, "\r\n");
+	assert_eq!(section.body[0].indent, "  ");
+	assert_eq!(
+		section.body[0].content,
+		LineContent::Comment {
+			indicator: '#',
+			value: String::from(" d"),
+		}
+	);
+}
+
+#[test]
+fn converts_to_ast() {
+	let document = parse_document("a = b\n\n[c]  \n  d=e\n").unwrap();
+	assert_eq!(document.to_ast().to_string(), "a=b\n\n[c]\nd=e\n");
+}
+
+#[test]
+fn rejects_unparsed_input() {
+	assert!(parse_document("[foo").is_err());
+	assert!(parse_document("a=b\nfoo\n").is_err());
+}
+
+// Editing
+
+#[test]
+fn set_property_replaces_value_only() {
+	let mut document = parse_document("[*]\n  indent_size =  2 ; note\n").unwrap();
+	let previous = document.set_property("*", "INDENT_SIZE", "4").unwrap();
+	assert_eq!(previous.as_deref(), Some("2 ; note"));
+	assert_eq!(document.to_string(), "[*]\n  indent_size =  4\n");
+}
+
+#[test]
+fn set_property_updates_last_matching_section_and_pair() {
+	let mut document = parse_document("[*]\na=1\na=2\n\n[*]\na=3\n\n[b]\na=4\n").unwrap();
+	document.set_property("*", "a", "5").unwrap();
+	assert_eq!(
+		document.to_string(),
+		"[*]\na=1\na=2\n\n[*]\na=5\n\n[b]\na=4\n"
+	);
+}
+
+#[test]
+fn set_property_adds_pair_after_last_line_of_section() {
+	let mut document = parse_document("[*]\r\na = 1\r\n# end\r\n\r\n[b]\r\n").unwrap();
+	document.set_property("*", "c", "2").unwrap();
+	assert_eq!(
+		document.to_string(),
+		"[*]\r\na = 1\r\n# end\r\nc = 2\r\n\r\n[b]\r\n"
+	);
+}
+
+#[test]
+fn set_property_adds_pair_to_empty_section() {
+	let mut document = parse_document("[a]\n\n[b]\n").unwrap();
+	document.set_property("a", "c", "d").unwrap();
+	assert_eq!(document.to_string(), "[a]\nc=d\n\n[b]\n");
+}
+
+#[test]
+fn set_property_keeps_missing_final_newline() {
+	let mut document = parse_document("[a]\nb=c").unwrap();
+	document.set_property("a", "d", "e").unwrap();
+	assert_eq!(document.to_string(), "[a]\nb=c\nd=e");
+}
+
+#[test]
+fn set_property_appends_section() {
+	let mut document = parse_document("root = true\n\n[*]\nindent_style = tab").unwrap();
+	document
+		.set_property("*.md", "indent_style", "space")
+		.unwrap();
+	assert_eq!(
+		document.to_string(),
+		"root = true\n\n[*]\nindent_style = tab\n\n[*.md]\nindent_style = space\n"
+	);
+
+	let mut document = parse_document("").unwrap();
+	document.set_property("*", "a", "b").unwrap();
+	assert_eq!(document.to_string(), "[*]\na=b\n");
+
+	let mut document = parse_document("[*]\na=b\n\n").unwrap();
+	document.set_property("c", "d", "e").unwrap();
+	assert_eq!(document.to_string(), "[*]\na=b\n\n[c]\nd=e\n");
+}
+
+#[test]
+fn set_property_rejects_what_would_not_parse_back() {
+	let contents = "[*]\na=b\n";
+	let mut document = parse_document(contents).unwrap();
+	let error = document.set_property("*", "a", "c\n[*.md]").unwrap_err();
+	assert_eq!(error.to_string(), "value contains a line break");
+	for (section, key, value) in [
+		("*", "a\rb", "c"),
+		("*\r\n", "a", "c"),
+		("", "a", "c"),
+		("*", "", "c"),
+		("*", "a=b", "c"),
+		("*", "[a", "c"),
+		("*", "#a", "c"),
+		("*", ";a", "c"),
+		("*", " a", "c"),
+		("*", "a", "c "),
+	] {
+		assert!(document.set_property(section, key, value).is_err());
+	}
+	assert_eq!(document.to_string(), contents);
+}
+
+#[test]
+fn set_properties_parse_back() {
+	let mut document = parse_document("[*]\na=b\n").unwrap();
+	for (section, key, value) in [("*", "a b", "c = d; e"), ("x]y", "k", ""), ("*", "a", "#")] {
+		document.set_property(section, key, value).unwrap();
+		let ast = one_ini::parse(&document.to_string()).unwrap();
+		let pairs: Vec<(&str, &str, &str)> = ast
+			.body
+			.iter()
+			.filter_map(|item| match item {
+				Item::Section(section) => Some(section),
+				_ => None,
+			})
+			.flat_map(|s| {
+				s.body.iter().filter_map(|item| match item {
+					Item::Pair(pair) => {
+						Some((s.name.as_str(), pair.key.as_str(), pair.value.as_str()))
+					}
+					_ => None,
+				})
+			})
+			.collect();
+		assert!(pairs.contains(&(section, key, value)), "{:?}", pairs);
+	}
+}
+
+#[test]
+fn remove_property_removes_only_its_lines() {
+	let mut document = parse_document("[*]\n a = 1\n\n b = 2\n A = 3\n[c]\na = 4\n").unwrap();
+	assert!(document.remove_property("*", "a"));
+	assert_eq!(document.to_string(), "[*]\n\n b = 2\n[c]\na = 4\n");
+	assert!(!document.remove_property("missing", "a"));
+}
+
+#[test]
+fn rename_section_changes_only_its_name() {
+	let mut document = parse_document(" [a] \r\nb=c\r\n[d]\r\n").unwrap();
+	assert_eq!(document.rename_section("a", "e"), Ok(true));
+	assert_eq!(document.to_string(), " [e] \r\nb=c\r\n[d]\r\n");
+	assert_eq!(document.rename_section("a", "e"), Ok(false));
+}
+
+#[test]
+fn renamed_sections_parse_back() {
+	let contents = "[a]\nb=c\n";
+	let mut document = parse_document(contents).unwrap();
+	assert!(document.rename_section("a", "").is_err());
+	assert!(document.rename_section("a", "e\n[f]").is_err());
+	assert!(document.rename_section("a", "e\r").is_err());
+	assert_eq!(document.to_string(), contents);
+	assert_eq!(document.rename_section("a", "[e] f]"), Ok(true));
+	let ast = one_ini::parse(&document.to_string()).unwrap();
+	let Item::Section(section) = &ast.body[0] else {
+		panic!()
+	};
+	assert_eq!(section.name, "[e] f]");
+}
+
+fn round_trip(contents: &str) -> one_ini::Document {
+	let document = parse_document(contents).unwrap();
+	assert_eq!(document.to_string(), contents);
+	document
+}
@@ -0,0 +1,121 @@
+//! Integration test suite for the `editorconfig` drop-in binary.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+#[test]
+fn prints_properties_of_one_file() {
+	let output = run(&[&fixture("lookup", "nested/src/main.rs")], "");
+	assert!(output.status.success());
+	assert_eq!(
+		stdout(&output),
+		"indent_style=space\nindent_size=4\ntab_width=4\n"
+	);
+}
+
+#[test]
+fn prints_headers_for_several_files() {
+	let first = fixture("lookup", "a.txt");
+	let second = fixture("lookup", "nested/root/b.txt");
+	let output = run(&[&first, &second], "");
+	assert_eq!(
+		stdout(&output),
+		format!(
+			"[{}]\nindent_style=space\nindent_size=2\ntab_width=2\n[{}]\nend_of_line=lf\n",
+			first, second
+		)
+	);
+}
+
+#[test]
+fn reads_file_paths_from_stdin() {
+	let first = fixture("lookup", "a.txt");
+	let second = fixture("lookup", "nested/root/b.txt");
+	let output = run(&["-"], &format!("{}\n{}\n", first, second));
+	assert_eq!(
+		stdout(&output),
+		format!(
+			"[{}]\nindent_style=space\nindent_size=2\ntab_width=2\n[{}]\nend_of_line=lf\n",
+			first, second
+		)
+	);
+}
+
+#[test]
+fn config_file_name() {
+	let path = fixture("core-test-cases/properties", "test.c");
+	let output = run(&["-f", "indent_size_default.in", &path], "");
+	assert_eq!(stdout(&output), "indent_style=tab\nindent_size=tab\n");
+}
+
+#[test]
+fn emulates_older_versions() {
+	let path = fixture("core-test-cases/properties", "test.c");
+	for version in ["0.8.0", "0.9.0", "0.9.9"] {
+		let output = run(&["-f", "indent_size_default.in", "-b", version, &path], "");
+		assert_eq!(stdout(&output), "indent_style=tab\n", "{}", version);
+	}
+	let output = run(&["-b", "0.10.0", "-f", "indent_size_default.in", &path], "");
+	assert_eq!(stdout(&output), "indent_style=tab\nindent_size=tab\n");
+}
+
+#[test]
+fn rejects_invalid_versions() {
+	let path = fixture("lookup", "a.txt");
+	for version in ["abc", "99.0"] {
+		let output = run(&["-b", version, &path], "");
+		assert_eq!(output.status.code(), Some(1));
+		assert!(stderr(&output).starts_with("editorconfig: "));
+	}
+}
+
+#[test]
+fn help_and_version() {
+	let output = run(&["-h"], "");
+	assert!(stdout(&output).starts_with("Usage: editorconfig [OPTIONS] FILEPATH1"));
+	let output = run(&["--version"], "");
+	assert!(stdout(&output).starts_with("EditorConfig one-ini Version "));
+}
+
+#[test]
+fn missing_file_path() {
+	let output = run(&[], "");
+	assert_eq!(output.status.code(), Some(1));
+	assert!(stderr(&output).contains("missing FILEPATH"));
+}
+
+fn fixture(dir: &str, path: &str) -> String {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/fixtures")
+		.join(dir)
+		.join(path)
+		.to_str()
+		.unwrap()
+		.to_string()
+}
+
+fn run(args: &[&str], stdin: &str) -> Output {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_editorconfig"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(stdin.as_bytes())
+		.unwrap();
+	child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+	String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+	String::from_utf8(output.stderr.clone()).unwrap()
+}
@@ -0,0 +1,419 @@
+//! Runs cases written after the
+//! [editorconfig-core-test](https://github.com/editorconfig/editorconfig-core-test)
+//! suite through the resolver, followed by local cases that the suite does
+//! not cover.
+//!
+//! Each case resolves a path relative to its suite's fixture directory,
+//! reading config files named after the `.in` file, just like the reference
+//! cores are run with `editorconfig -f <file>.in <path>`.
+
+use one_ini::{find_configs, resolve_for_version, OneINIAST, Version};
+use std::path::{Path, PathBuf};
+
+// glob
+
+#[test]
+fn star() {
+	assert_resolves("glob", "star.in", "ae.c", &["key=value", "keyc=valuec"]);
+	assert_resolves("glob", "star.in", "abcde.c", &["key=value", "keyc=valuec"]);
+	assert_resolves("glob", "star.in", "a/e.c", &["keyc=valuec"]);
+	assert_resolves(
+		"glob",
+		"star.in",
+		"Bar/foo.txt",
+		&["keyb=valueb", "keyc=valuec"],
+	);
+	assert_resolves(
+		"glob",
+		"star.in",
+		"Bar/.editorconfig",
+		&["keyb=valueb", "keyc=valuec"],
+	);
+}
+
+#[test]
+fn star_star() {
+	for path in [
+		"az.c", "a/z.c", "amnz.c", "am/nz.c", "a/mnz.c", "amn/z.c", "a/mn/z.c",
+	] {
+		assert_resolves("glob", "star_star.in", path, &["key1=value1"]);
+	}
+	for path in ["b/z.c", "b/mnz.c", "b/mn/z.c"] {
+		assert_resolves("glob", "star_star.in", path, &["key2=value2"]);
+	}
+	for path in ["c/z.c", "cmn/z.c", "c/mn/z.c"] {
+		assert_resolves("glob", "star_star.in", path, &["key3=value3"]);
+	}
+	for path in ["d/z.c", "d/mn/z.c"] {
+		assert_resolves("glob", "star_star.in", path, &["key4=value4"]);
+	}
+	assert_resolves("glob", "star_star.in", "d/mnz.c", &[]);
+}
+
+#[test]
+fn question() {
+	assert_resolves("glob", "question.in", "some.c", &["key=value"]);
+	assert_resolves("glob", "question.in", "som.c", &[]);
+	assert_resolves("glob", "question.in", "someo.c", &[]);
+	assert_resolves("glob", "question.in", "som/.c", &[]);
+}
+
+#[test]
+fn brackets() {
+	assert_resolves("glob", "brackets.in", "a.a", &["choice=true"]);
+	assert_resolves("glob", "brackets.in", "c.a", &[]);
+	assert_resolves("glob", "brackets.in", "c.b", &["choice=false"]);
+	assert_resolves("glob", "brackets.in", "a.b", &[]);
+	assert_resolves("glob", "brackets.in", "f.c", &["range=true"]);
+	assert_resolves("glob", "brackets.in", "h.c", &[]);
+	assert_resolves("glob", "brackets.in", "h.d", &["range=false"]);
+	assert_resolves("glob", "brackets.in", "f.d", &[]);
+	assert_resolves("glob", "brackets.in", "].e", &["close_inside=true"]);
+	assert_resolves("glob", "brackets.in", "b.e", &["close_inside=true"]);
+	assert_resolves("glob", "brackets.in", "c.f", &["close_inside=false"]);
+	assert_resolves("glob", "brackets.in", "].f", &[]);
+	assert_resolves("glob", "brackets.in", "].g", &["close_end=true"]);
+	assert_resolves("glob", "brackets.in", "ab[e/]cd.i", &["slash_inside=true"]);
+	assert_resolves("glob", "brackets.in", "ab/cd.i", &[]);
+	assert_resolves("glob", "brackets.in", "ab[cd.j", &["unclosed=true"]);
+}
+
+#[test]
+fn braces() {
+	for path in ["test.py", "test.js", "test.html"] {
+		assert_resolves("glob", "braces.in", path, &["choice=true"]);
+	}
+	assert_resolves("glob", "braces.in", "test.pyc", &[]);
+	assert_resolves("glob", "braces.in", "{single}.b", &["choice=single"]);
+	assert_resolves("glob", "braces.in", "single.b", &[]);
+	assert_resolves("glob", "braces.in", "{}.c", &["empty=all"]);
+	assert_resolves("glob", "braces.in", ".c", &[]);
+	for path in ["ab.d", "ac.d", "a.d"] {
+		assert_resolves("glob", "braces.in", path, &["empty=word"]);
+	}
+	assert_resolves("glob", "braces.in", "{.f", &["closing=false"]);
+	for path in ["word.g", "{also}.g", "this.g"] {
+		assert_resolves("glob", "braces.in", path, &["nested=true"]);
+	}
+	assert_resolves("glob", "braces.in", "also.g", &[]);
+	for path in ["a.l", "b.l", "c.l"] {
+		assert_resolves("glob", "braces.in", path, &["nested_end=true"]);
+	}
+	assert_resolves("glob", "braces.in", "a,b.txt", &["comma=yes"]);
+	assert_resolves("glob", "braces.in", "cd.txt", &["comma=yes"]);
+	assert_resolves("glob", "braces.in", "a.txt", &[]);
+	for path in ["some.j", "abe.j", "abf.j", "adce.j"] {
+		assert_resolves("glob", "braces.in", path, &["patterns=nested"]);
+	}
+	assert_resolves("glob", "braces.in", "ab.j", &[]);
+	for path in ["3", "15", "60", "120"] {
+		assert_resolves("glob", "braces.in", path, &["number=true"]);
+	}
+	for path in ["1", "5a", "121", "060a"] {
+		assert_resolves("glob", "braces.in", path, &[]);
+	}
+	assert_resolves("glob", "braces.in", "{aardvark..antelope}", &["words=a"]);
+	assert_resolves("glob", "braces.in", "ant", &[]);
+}
+
+#[test]
+fn utf8char() {
+	assert_resolves("glob", "utf8char.in", "中文.txt", &["key=value"]);
+	assert_resolves("glob", "utf8char.in", "dir/中文.txt", &["key=value"]);
+}
+
+// properties
+
+#[test]
+fn lowercase_values() {
+	assert_resolves(
+		"properties",
+		"lowercase_values.in",
+		"test1.c",
+		&["end_of_line=crlf", "indent_style=space"],
+	);
+	assert_resolves(
+		"properties",
+		"lowercase_values.in",
+		"test2.c",
+		&[
+			"insert_final_newline=true",
+			"trim_trailing_whitespace=false",
+			"charset=utf-8",
+		],
+	);
+}
+
+#[test]
+fn lowercase_names() {
+	assert_resolves(
+		"properties",
+		"lowercase_names.in",
+		"test.c",
+		&["testproperty=testvalue"],
+	);
+}
+
+#[test]
+fn tab_width_default() {
+	assert_resolves(
+		"properties",
+		"tab_width_default.in",
+		"test.c",
+		&["indent_size=4", "tab_width=4"],
+	);
+}
+
+#[test]
+fn indent_size_default_pre_0_10_0() {
+	assert_resolves_for_version(
+		"properties",
+		"indent_size_default.in",
+		"test.c",
+		Version::new(0, 9, 9),
+		&["indent_style=tab"],
+	);
+}
+
+#[test]
+fn indent_size_default() {
+	assert_resolves(
+		"properties",
+		"indent_size_default.in",
+		"test.c",
+		&["indent_style=tab", "indent_size=tab"],
+	);
+	assert_resolves(
+		"properties",
+		"indent_size_default.in",
+		"test2.c",
+		&["indent_style=tab", "indent_size=2", "tab_width=2"],
+	);
+	assert_resolves(
+		"properties",
+		"indent_size_default.in",
+		"test3.c",
+		&["indent_style=tab", "tab_width=4", "indent_size=4"],
+	);
+	assert_resolves(
+		"properties",
+		"indent_size_default.in",
+		"test4.c",
+		&["indent_size=8", "tab_width=8"],
+	);
+}
+
+#[test]
+fn override_sections() {
+	assert_resolves(
+		"properties",
+		"override.in",
+		"test.c",
+		&["indent_style=tab", "charset=utf-8", "indent_size=tab"],
+	);
+	assert_resolves(
+		"properties",
+		"override.in",
+		"test.h",
+		&["indent_style=space", "charset=utf-8"],
+	);
+}
+
+// parser
+
+#[test]
+fn basic() {
+	assert_resolves(
+		"parser",
+		"basic.in",
+		"a.a",
+		&["option1=value1", "option2=value2"],
+	);
+	assert_resolves("parser", "basic.in", "a.b", &["option1=a", "option2=a"]);
+	assert_resolves("parser", "basic.in", "b.b", &["option1=a", "option2=b"]);
+	assert_resolves("parser", "basic.in", "a.c", &["option=space around equals"]);
+}
+
+#[test]
+fn whitespace() {
+	for path in ["test1.c", "test2.c", "test3.c", "test4.c", "test5.c"] {
+		assert_resolves("parser", "whitespace.in", path, &["key=value"]);
+	}
+	assert_resolves(
+		"parser",
+		"whitespace.in",
+		"test6.c",
+		&["key1=value1", "key2=value2"],
+	);
+	assert_resolves("parser", "whitespace.in", " test 7 ", &["key=value"]);
+	assert_resolves("parser", "whitespace.in", "test8.c", &["key=value"]);
+	assert_resolves("parser", "whitespace.in", "test9.c", &["key=value"]);
+	assert_resolves(
+		"parser",
+		"whitespace.in",
+		"test10.c",
+		&["key1=value1", "key2=value2", "key3=value3"],
+	);
+	assert_resolves("parser", "whitespace.in", "test11.c", &["key=value"]);
+}
+
+#[test]
+fn comments() {
+	assert_resolves("parser", "comments.in", "test1.c", &["key=value"]);
+	assert_resolves("parser", "comments.in", "test2.c", &["key=value"]);
+	assert_resolves(
+		"parser",
+		"comments.in",
+		"test3.c",
+		&["key1=value1", "key2=value2"],
+	);
+	assert_resolves(
+		"parser",
+		"comments.in",
+		"test4.c",
+		&["key=value; not a comment"],
+	);
+	assert_resolves(
+		"parser",
+		"comments.in",
+		"test5.c",
+		&["key=value # not a comment"],
+	);
+	assert_resolves("parser", "comments.in", "test6;.c", &["key=value"]);
+	assert_resolves("parser", "comments.in", "test7#.c", &["key=value"]);
+}
+
+#[test]
+fn bom() {
+	assert_resolves("parser", "bom.in", "a.c", &["key=value"]);
+}
+
+#[test]
+fn crlf() {
+	assert_resolves("parser", "crlf.in", "a.c", &["key=value"]);
+}
+
+// filetree
+
+#[test]
+fn parent_directory() {
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"parent_directory/test.a",
+		&["key=root", "key_parent=parent"],
+	);
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"parent_directory/deeper/test.a",
+		&["key=root"],
+	);
+}
+
+#[test]
+fn path_separator() {
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"path/separator",
+		&["key=root", "key_separator=value"],
+	);
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"path\\separator",
+		&["key=root"],
+	);
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"top/of/path/separator",
+		&["key=root"],
+	);
+}
+
+#[test]
+fn nested_override() {
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"nested/test.c",
+		&["key=nested", "key_nested=from_parent"],
+	);
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"nested/deeper/test.h",
+		&["key=nested"],
+	);
+}
+
+#[test]
+fn root_file() {
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"root_file/test.a",
+		&["child=true"],
+	);
+}
+
+#[test]
+fn root_mixed_case() {
+	assert_resolves(
+		"filetree",
+		".editorconfig",
+		"root_mixed/test.a",
+		&["mixed=true"],
+	);
+}
+
+// local
+
+#[test]
+fn commented_out_pairs() {
+	assert_resolves_locally("parser", "commented_out_pairs.in", "a.c", &["key=value"]);
+}
+
+fn fixture_dir(cases: &str, suite: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/fixtures")
+		.join(cases)
+		.join(suite)
+}
+
+fn assert_resolves(suite: &str, config: &str, path: &str, expected: &[&str]) {
+	assert_resolves_for_version(suite, config, path, Version::CURRENT, expected);
+}
+
+fn assert_resolves_for_version(
+	suite: &str,
+	config: &str,
+	path: &str,
+	version: Version,
+	expected: &[&str],
+) {
+	let dir = fixture_dir("core-test-cases", suite);
+	assert_resolves_in(&dir, config, path, version, expected);
+}
+
+fn assert_resolves_locally(suite: &str, config: &str, path: &str, expected: &[&str]) {
+	let dir = fixture_dir("local-cases", suite);
+	assert_resolves_in(&dir, config, path, Version::CURRENT, expected);
+}
+
+fn assert_resolves_in(dir: &Path, config: &str, path: &str, version: Version, expected: &[&str]) {
+	let path = dir.join(path);
+	let configs = find_configs(&path, config).unwrap();
+	let configs: Vec<(&Path, &OneINIAST)> = configs
+		.iter()
+		.map(|(dir, ast)| (dir.as_path(), ast))
+		.collect();
+	let properties = resolve_for_version(&path, &configs, version);
+	let actual: Vec<String> = properties
+		.iter()
+		.map(|(key, value)| format!("{}={}", key, value))
+		.collect();
+	assert_eq!(actual, expected, "{}", path.display());
+}
@@ -0,0 +1,155 @@
+//! Integration test suite for offset encodings.
+
+use one_ini::{
+	encode_offsets, parse_to_vec, parse_to_vec_with_trivia, Item, OffsetEncoding, Position,
+};
+
+const CONTENTS: &str = "\u{feff}[é]\r\nkey = 😀 ü\n; ∑ comment\n";
+
+/// The offset of each character boundary in the encoding, by byte offset.
+fn boundaries(contents: &str, encoding: OffsetEncoding) -> Vec<(usize, usize)> {
+	let mut encoded = 0;
+	let mut boundaries = vec![];
+	for (i, c) in contents.char_indices() {
+		boundaries.push((i, encoded));
+		encoded += match encoding {
+			OffsetEncoding::Utf8 => c.len_utf8(),
+			OffsetEncoding::Utf16 => c.encode_utf16(&mut [0; 2]).len(),
+			OffsetEncoding::CodePoint => 1,
+		};
+	}
+	boundaries.push((contents.len(), encoded));
+	boundaries
+}
+
+#[test]
+fn offsets_of_every_character_boundary() {
+	for encoding in [
+		OffsetEncoding::Utf8,
+		OffsetEncoding::Utf16,
+		OffsetEncoding::CodePoint,
+	] {
+		for (byte, encoded) in boundaries(CONTENTS, encoding) {
+			assert_eq!(encoding.offset(CONTENTS, byte), encoded, "{}", encoding);
+		}
+	}
+}
+
+#[test]
+fn encode_token_offsets() {
+	let mut tokens = parse_to_vec_with_trivia(CONTENTS);
+	let expected: Vec<u32> = tokens
+		.chunks(3)
+		.flat_map(|token| {
+			let offset = |i: u32| OffsetEncoding::Utf16.offset(CONTENTS, i as usize) as u32;
+			[token[0], offset(token[1]), offset(token[2])]
+		})
+		.collect();
+	encode_offsets(CONTENTS, &mut tokens, OffsetEncoding::Utf16);
+	assert_eq!(tokens, expected);
+	// The trivia tokens still cover the string, now in UTF-16 code units.
+	assert_eq!(
+		tokens[tokens.len() - 1] as usize,
+		CONTENTS.encode_utf16().count()
+	);
+}
+
+#[test]
+fn encode_token_offsets_as_code_points() {
+	let contents = "😀=😀\n";
+	let mut tokens = parse_to_vec(contents).unwrap();
+	encode_offsets(contents, &mut tokens, OffsetEncoding::CodePoint);
+	assert_eq!(tokens, [0, 0, 1, 1, 2, 3]);
+}
+
+#[test]
+fn encode_offsets_out_of_order() {
+	let contents = "é=ü";
+	let mut tokens = vec![1, 3, 5, 0, 0, 2];
+	encode_offsets(contents, &mut tokens, OffsetEncoding::Utf16);
+	assert_eq!(tokens, [1, 2, 3, 0, 0, 1]);
+}
+
+#[test]
+fn utf8_offsets_are_unchanged() {
+	let mut tokens = parse_to_vec(CONTENTS).unwrap();
+	let expected = tokens.clone();
+	encode_offsets(CONTENTS, &mut tokens, OffsetEncoding::Utf8);
+	assert_eq!(tokens, expected);
+}
+
+#[test]
+fn encode_offsets_clamps_out_of_range_offsets() {
+	let contents = "é=ü";
+	let mut tokens = vec![0, 1, 99];
+	encode_offsets(contents, &mut tokens, OffsetEncoding::Utf16);
+	assert_eq!(tokens, [0, 0, 3]);
+	let mut tokens = vec![0, 1, 99];
+	encode_offsets(contents, &mut tokens, OffsetEncoding::Utf8);
+	assert_eq!(tokens, [0, 0, 5]);
+}
+
+#[test]
+fn encoded_positions_clamp_out_of_range_offsets() {
+	let contents = "a=é\nb";
+	for (offset, expected) in [(3, (2, 3)), (99, (5, 2))] {
+		let position = Position {
+			offset,
+			line: 1,
+			column: 1,
+		};
+		let utf16 = position.encoded(contents, OffsetEncoding::Utf16);
+		assert_eq!((utf16.offset, utf16.column), expected);
+	}
+}
+
+#[test]
+fn encoded_spans() {
+	let ast = one_ini::parse(CONTENTS).unwrap();
+	let Item::Section(section) = &ast.body[0] else {
+		panic!()
+	};
+	let Item::Pair(pair) = &section.body[0] else {
+		panic!()
+	};
+	let span = pair.value_span.unwrap();
+	assert_eq!(
+		(span.start.offset, span.start.column, span.end.column),
+		(15, 7, 10)
+	);
+
+	let utf16 = span.encoded(CONTENTS, OffsetEncoding::Utf16);
+	assert_eq!((utf16.start.offset, utf16.end.offset), (12, 16));
+	assert_eq!((utf16.start.line, utf16.end.line), (2, 2));
+	assert_eq!((utf16.start.column, utf16.end.column), (7, 11));
+
+	assert_eq!(
+		span.encoded(CONTENTS, OffsetEncoding::CodePoint).end.column,
+		10
+	);
+	assert_eq!(span.encoded(CONTENTS, OffsetEncoding::Utf8).end.column, 14);
+}
+
+#[test]
+fn parse_encodings() {
+	for (name, encoding) in [
+		("utf8", OffsetEncoding::Utf8),
+		("UTF-8", OffsetEncoding::Utf8),
+		("utf16", OffsetEncoding::Utf16),
+		("utf-16", OffsetEncoding::Utf16),
+		("codepoint", OffsetEncoding::CodePoint),
+	] {
+		assert_eq!(name.parse(), Ok(encoding));
+	}
+	for encoding in [
+		OffsetEncoding::Utf8,
+		OffsetEncoding::Utf16,
+		OffsetEncoding::CodePoint,
+	] {
+		assert_eq!(encoding.to_string().parse(), Ok(encoding));
+	}
+	assert_eq!(
+		"latin1".parse::<OffsetEncoding>().unwrap_err().to_string(),
+		"invalid offset encoding `latin1`, expected `utf8`, `utf16` or `codepoint`"
+	);
+}
@@ -0,0 +1,120 @@
+//! Integration test suite for fixing files to follow their properties.
+
+use one_ini::{check, fix, Properties};
+
+#[test]
+fn converts_line_endings() {
+	let properties = Properties::from_iter([("end_of_line", "crlf")]);
+	assert_eq!(fix(b"a\nb\rc\r\nd", &properties), b"a\r\nb\r\nc\r\nd");
+	let properties = Properties::from_iter([("end_of_line", "cr")]);
+	assert_eq!(fix(b"a\r\n\nb\n", &properties), b"a\r\rb\r");
+}
+
+#[test]
+fn strips_trailing_whitespace() {
+	let properties = Properties::from_iter([("trim_trailing_whitespace", "true")]);
+	assert_eq!(fix(b"a \t\n  \r\n\tb", &properties), b"a\n\r\n\tb");
+	let properties = Properties::from_iter([("trim_trailing_whitespace", "false")]);
+	assert_eq!(fix(b"a \n", &properties), b"a \n");
+}
+
+#[test]
+fn adds_a_final_newline() {
+	let properties = Properties::from_iter([("insert_final_newline", "true")]);
+	assert_eq!(fix(b"a\r\nb", &properties), b"a\r\nb\r\n");
+	assert_eq!(fix(b"a", &properties), b"a\n");
+	assert_eq!(fix(b"a\n", &properties), b"a\n");
+	assert_eq!(fix(b"", &properties), b"");
+	let properties =
+		Properties::from_iter([("insert_final_newline", "true"), ("end_of_line", "cr")]);
+	assert_eq!(fix(b"a\nb", &properties), b"a\rb\r");
+}
+
+#[test]
+fn removes_final_newlines() {
+	let properties = Properties::from_iter([("insert_final_newline", "false")]);
+	assert_eq!(fix(b"a\nb\n\r\n", &properties), b"a\nb");
+	assert_eq!(fix(b"\xef\xbb\xbf\n", &properties), b"\xef\xbb\xbf");
+}
+
+#[test]
+fn reindents_with_spaces() {
+	let properties = Properties::from_iter([("indent_style", "space"), ("indent_size", "4")]);
+	assert_eq!(
+		fix(b"a\n\tb\n\t\tc\n  \td\n\t\n", &properties),
+		b"a\n    b\n        c\n    d\n\t\n"
+	);
+}
+
+#[test]
+fn reindents_with_tabs() {
+	let properties = Properties::from_iter([("indent_style", "tab"), ("indent_size", "2")]);
+	assert_eq!(
+		fix(b"a\n  b\n    c\n   * d\n \te\n", &properties),
+		b"a\n\tb\n\t\tc\n\t * d\n\te\n"
+	);
+}
+
+#[test]
+fn reindents_only_with_a_known_width() {
+	let properties = Properties::from_iter([("indent_style", "space")]);
+	assert_eq!(fix(b"\ta\n", &properties), b"\ta\n");
+}
+
+#[test]
+fn transcodes_between_utf8_and_utf8_bom() {
+	let utf8 = Properties::from_iter([("charset", "utf-8")]);
+	let utf8_bom = Properties::from_iter([("charset", "utf-8-bom")]);
+	assert_eq!(fix(b"\xef\xbb\xbfa\n", &utf8), b"a\n");
+	assert_eq!(fix(b"a\n", &utf8_bom), b"\xef\xbb\xbfa\n");
+	assert_eq!(fix(b"\xef\xbb\xbfa\n", &utf8_bom), b"\xef\xbb\xbfa\n");
+	assert_eq!(fix(b"", &utf8_bom), b"\xef\xbb\xbf");
+}
+
+#[test]
+fn removes_a_utf8_bom_from_latin1() {
+	let properties = Properties::from_iter([("charset", "latin1")]);
+	let fixed = fix(b"\xef\xbb\xbfa\xe9\n", &properties);
+	assert_eq!(fixed, b"a\xe9\n");
+	assert_eq!(check(&fixed, &properties), []);
+}
+
+#[test]
+fn leaves_utf16_alone() {
+	let properties = Properties::from_iter([("charset", "utf-16le"), ("end_of_line", "crlf")]);
+	assert_eq!(fix(b"\xff\xfea\0\n\0", &properties), b"\xff\xfea\0\n\0");
+}
+
+#[test]
+fn fixed_contents_pass_the_check() {
+	let properties = Properties::from_iter([
+		("indent_style", "tab"),
+		("indent_size", "4"),
+		("end_of_line", "lf"),
+		("charset", "utf-8-bom"),
+		("trim_trailing_whitespace", "true"),
+		("insert_final_newline", "true"),
+	]);
+	let contents =
+		b"fn main() {  \r\n    let a = 1;\t\r\n  \r\n\t  /**\r\n\t   * a\r\n\t   */\r\n}";
+	assert!(!check(contents, &properties).is_empty());
+	let fixed = fix(contents, &properties);
+	assert_eq!(check(&fixed, &properties), []);
+	assert_eq!(fix(&fixed, &properties), fixed);
+}
+
+#[test]
+fn fixed_tab_indentation_passes_the_check() {
+	let properties = Properties::from_iter([("indent_style", "tab"), ("tab_width", "8")]);
+	for contents in [
+		&b" a\n"[..],
+		b"    a\n",
+		b"\t        a\n",
+		b" \t a\n",
+		b"\t   a\n",
+	] {
+		let fixed = fix(contents, &properties);
+		assert_eq!(check(&fixed, &properties), [], "{:?}", fixed);
+		assert_eq!(fix(&fixed, &properties), fixed);
+	}
+}
@@ -0,0 +1,165 @@
+//! Integration test suite for formatting.
+
+use one_ini::{format, parse, CommentIndicator, EndOfLine, FormatOptions};
+
+const CONTENTS: &str = "\
+root = true
+
+# Unix-style newlines
+[*]
+END_OF_LINE = lf
+insert_final_newline = true
+
+; Tab indentation
+[Makefile]
+indent_style = tab
+";
+
+#[test]
+fn default_options_match_display() {
+	let formatted = format(CONTENTS, FormatOptions::default());
+	assert_eq!(formatted, parse(CONTENTS).unwrap().to_string());
+	assert_eq!(
+		formatted,
+		"root=true\n# Unix-style newlines\n\n[*]\nEND_OF_LINE=lf\ninsert_final_newline=true\n; Tab indentation\n\n[Makefile]\nindent_style=tab\n"
+	);
+}
+
+#[test]
+fn spaces_around_equals() {
+	let options = FormatOptions {
+		spaces_around_equals: true,
+		..Default::default()
+	};
+	assert_eq!(format("a=b\nc=\n", options), "a = b\nc =\n");
+}
+
+#[test]
+fn commented_out_pairs_are_kept_as_comments() {
+	let options = FormatOptions {
+		spaces_around_equals: true,
+		lowercase_keys: true,
+		..Default::default()
+	};
+	assert_eq!(
+		format("[*]\n# Indent_Size=4\nA=b\n", options),
+		"[*]\n# Indent_Size=4\na = b\n"
+	);
+}
+
+#[test]
+fn lowercase_keys() {
+	let options = FormatOptions {
+		lowercase_keys: true,
+		..Default::default()
+	};
+	assert_eq!(
+		format("[*]\nEnd_Of_Line=LF\n", options),
+		"[*]\nend_of_line=LF\n"
+	);
+}
+
+#[test]
+fn blank_lines_between_sections() {
+	let options = FormatOptions {
+		blank_lines_between_sections: 2,
+		..Default::default()
+	};
+	assert_eq!(format("[a]\n[b]\n", options), "[a]\n\n\n[b]\n");
+	let options = FormatOptions {
+		blank_lines_between_sections: 0,
+		..Default::default()
+	};
+	assert_eq!(format("a=b\n\n[a]\n\n[b]\n", options), "a=b\n[a]\n[b]\n");
+}
+
+#[test]
+fn comment_indicator() {
+	let options = FormatOptions {
+		comment_indicator: Some(CommentIndicator::Semicolon),
+		..Default::default()
+	};
+	assert_eq!(format("# a\n[*]\n; b\n", options), "; a\n\n[*]\n; b\n");
+}
+
+#[test]
+fn line_endings() {
+	let options = FormatOptions {
+		end_of_line: EndOfLine::Crlf,
+		..Default::default()
+	};
+	assert_eq!(
+		format("a=b\n[*]\rc=d", options),
+		"a=b\r\n\r\n[*]\r\nc=d\r\n"
+	);
+}
+
+#[test]
+fn align_equals_within_each_section() {
+	let options = FormatOptions {
+		spaces_around_equals: true,
+		align_equals: true,
+		..Default::default()
+	};
+	assert_eq!(
+		format(
+			"root=true\n[*]\nindent_style=space\n# note\nindent_size=2\n[*.md]\nx=y\n",
+			options
+		),
+		"root = true\n\n[*]\nindent_style = space\n# note\nindent_size  = 2\n\n[*.md]\nx = y\n"
+	);
+}
+
+#[test]
+fn invalid_lines_are_kept() {
+	let formatted = format("[*]\n  not a pair  \na = b\n", FormatOptions::default());
+	assert_eq!(formatted, "[*]\nnot a pair\na=b\n");
+}
+
+#[test]
+fn byte_order_mark_is_kept() {
+	assert_eq!(
+		format("\u{feff}a = b\n", FormatOptions::default()),
+		"\u{feff}a=b\n"
+	);
+}
+
+#[test]
+fn formatting_is_idempotent() {
+	let options = FormatOptions {
+		spaces_around_equals: true,
+		lowercase_keys: true,
+		comment_indicator: Some(CommentIndicator::Hash),
+		end_of_line: EndOfLine::Crlf,
+		align_equals: true,
+		..Default::default()
+	};
+	let once = format(CONTENTS, options.clone());
+	assert_eq!(format(&once, options), once);
+}
+
+#[test]
+fn options_deserialize_from_camel_case() {
+	let options: FormatOptions =
+		serde_json::from_str(r#"{"spacesAroundEquals":true,"endOfLine":"crlf"}"#).unwrap();
+	assert_eq!(
+		options,
+		FormatOptions {
+			spaces_around_equals: true,
+			end_of_line: EndOfLine::Crlf,
+			..Default::default()
+		}
+	);
+}
+
+#[test]
+fn comment_indicator_deserializes_from_its_character() {
+	let options: FormatOptions = serde_json::from_str(r#"{"commentIndicator":";"}"#).unwrap();
+	assert_eq!(options.comment_indicator, Some(CommentIndicator::Semicolon));
+	let error = serde_json::from_str::<FormatOptions>(r#"{"commentIndicator":"x"}"#).unwrap_err();
+	assert!(
+		error.to_string().contains("unknown variant `x`"),
+		"{}",
+		error
+	);
+}
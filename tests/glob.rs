@@ -0,0 +1,129 @@
+//! Integration test suite for section name globs.
+
+use one_ini::{parse, Glob, Item};
+
+#[test]
+fn star_does_not_cross_separators() {
+	assert_matches(
+		"a*e.c",
+		&["ae.c", "abcde.c", "dir/abcde.c"],
+		&["a/e.c", "abcde.cc"],
+	);
+	assert_matches("/a*e.c", &["abcde.c"], &["a/b/e.c", "dir/ae.c"]);
+}
+
+#[test]
+fn double_star_crosses_separators() {
+	assert_matches("a**z.c", &["az.c", "a/z.c", "a/b/c/z.c"], &["a/b/c/z.cc"]);
+	assert_matches(
+		"a/**/z.c",
+		&["a/z.c", "a/b/z.c", "a/b/c/z.c"],
+		&["az.c", "b/a/z.c"],
+	);
+}
+
+#[test]
+fn question_mark_matches_one_character() {
+	assert_matches(
+		"som?.c",
+		&["some.c", "dir/somé.c"],
+		&["som.c", "someo.c", "som/.c"],
+	);
+}
+
+#[test]
+fn brackets() {
+	assert_matches("[ab].a", &["a.a", "b.a"], &["c.a"]);
+	assert_matches("[!ab].b", &["c.b"], &["a.b", "b.b"]);
+	assert_matches("[d-g].c", &["d.c", "f.c", "g.c"], &["c.c", "h.c"]);
+	assert_matches("[!d-g].d", &["c.d", "h.d"], &["d.d", "g.d"]);
+	assert_matches("[]ab].e", &["].e", "a.e"], &["c.e"]);
+	assert_matches("[!]ab].f", &["c.f"], &["].f", "a.f"]);
+	assert_matches("[ab\\]].g", &["].g", "a.g"], &["c.g"]);
+}
+
+#[test]
+fn brackets_with_separator_are_literal() {
+	assert_matches("ab[e/]cd.i", &["ab[e/]cd.i"], &["abecd.i", "ab/cd.i"]);
+}
+
+#[test]
+fn unclosed_bracket_is_literal() {
+	assert_matches("ab[c", &["ab[c"], &["abc"]);
+}
+
+#[test]
+fn braces() {
+	assert_matches(
+		"*.{py,js,html}",
+		&["a.py", "b/c.js", "d.html"],
+		&["e.rs", "f.pyjs"],
+	);
+	assert_matches("{single}.b", &["{single}.b"], &["single.b"]);
+	assert_matches("{}.c", &["{}.c"], &[".c"]);
+	assert_matches("{.h", &["{.h"], &[".h"]);
+	assert_matches("{,a}.d", &[".d", "a.d"], &["b.d"]);
+	assert_matches("{a,{b,c}}.e", &["a.e", "b.e", "c.e"], &["d.e"]);
+	assert_matches("{a\\,b,c}.f", &["a,b.f", "c.f"], &["a.f", "b.f"]);
+	assert_matches("{**/x,y}.g", &["a/b/x.g", "y.g"], &["z.g"]);
+}
+
+#[test]
+fn numeric_ranges() {
+	assert_matches(
+		"{3..120}",
+		&["3", "15", "60", "120"],
+		&["1", "121", "5a", "a"],
+	);
+	assert_matches("{-3..3}.x", &["-3.x", "0.x", "+3.x"], &["-4.x", "4.x"]);
+	assert_matches("{10..1}", &["5"], &["0", "11"]);
+	assert_matches("{1..a}", &["{1..a}"], &["1"]);
+}
+
+#[test]
+fn backslash_escapes() {
+	assert_matches("\\*.c", &["*.c"], &["a.c"]);
+	assert_matches("\\[ab].c", &["[ab].c"], &["a.c"]);
+	assert_matches("a\\{b,c}.c", &["a{b,c}.c"], &["ab.c"]);
+}
+
+#[test]
+fn escaped_comment_indicator_in_section_name() {
+	let ast = parse("[a\\;b]\n[a\\#b]").unwrap();
+	let globs: Vec<Glob> = ast
+		.body
+		.iter()
+		.map(|item| match item {
+			Item::Section(section) => section.glob(),
+			_ => unreachable!(),
+		})
+		.collect();
+	assert!(globs[0].is_match("a;b"));
+	assert!(!globs[0].is_match("a\\;b"));
+	assert!(globs[1].is_match("dir/a#b"));
+}
+
+#[test]
+fn many_stars_do_not_backtrack_exponentially() {
+	let text = "a".repeat(60);
+	let glob = Glob::new("*a*a*a*a*a*a*a*a*a*a*b");
+	assert!(!glob.is_match(&text));
+	assert!(glob.is_match(&(text.clone() + "b")));
+	let glob = Glob::new("{*a,*a*}{*a,*a*}{*a,*a*}{*a,*a*}b");
+	assert!(!glob.is_match(&text));
+}
+
+fn assert_matches(pattern: &str, matches: &[&str], mismatches: &[&str]) {
+	let glob = Glob::new(pattern);
+	for path in matches {
+		assert!(glob.is_match(path), "{} should match {}", pattern, path);
+	}
+	for path in mismatches {
+		assert!(
+			!glob.is_match(path),
+			"{} should not match {}",
+			pattern,
+			path
+		);
+	}
+}
@@ -1,6 +1,6 @@
 //! Integration test suite for the parse function.
 
-use one_ini::{parse, parse_to_vec, TokenTypes};
+use one_ini::{parse, parse_to_vec, ParseErrorKind, TokenTypes};
 use std::str;
 
 // Whitespace tests
@@ -80,6 +80,11 @@ fn comment_indicator_included_in_value() {
 	compare("a=b# c", "a=b# c\n");
 }
 
+#[test]
+fn commented_out_pair() {
+	compare("[a]\n# b = c\n;d=e\n", "[a]\n# b = c\n;d=e\n");
+}
+
 #[test]
 fn escaped_comment_indicator_in_value() {
 	compare("a=b\\;c", "a=b\\;c\n");
@@ -224,6 +229,19 @@ fn comment_indicator_in_section_before_pair_vec() {
 	);
 }
 
+#[test]
+fn commented_out_pair_vec() {
+	compare_vec(
+		"# a = b\n;c=d",
+		&vec![
+			(TokenTypes::CommentIndicator, "#"),
+			(TokenTypes::CommentValue, " a = b"),
+			(TokenTypes::CommentIndicator, ";"),
+			(TokenTypes::CommentValue, "c=d"),
+		],
+	);
+}
+
 #[test]
 fn comment_indicator_in_section_between_pairs_vec() {
 	compare_vec(
@@ -308,7 +326,70 @@ fn crlf_line_separators_vec() {
 
 #[test]
 fn partial_section_vec() {
-	compare_vec("[foo", &vec![]);
+	let error = parse_to_vec("[foo").unwrap_err();
+	assert_eq!(error.kind, ParseErrorKind::UnterminatedSectionHeader);
+}
+
+// Tests for parse errors
+
+#[test]
+fn unterminated_section_header() {
+	compare_error("[a", ParseErrorKind::UnterminatedSectionHeader, 0, 1, 1);
+	compare_error(
+		"a=b\n  [c]d\n",
+		ParseErrorKind::UnterminatedSectionHeader,
+		6,
+		2,
+		3,
+	);
+}
+
+#[test]
+fn key_starts_with_bracket() {
+	compare_error("[a]\n[b=c\n", ParseErrorKind::KeyStartsWithBracket, 4, 2, 1);
+}
+
+#[test]
+fn missing_equals() {
+	compare_error(
+		"[a]\r\nb=c\r\nd\r\n",
+		ParseErrorKind::MissingEquals,
+		10,
+		3,
+		1,
+	);
+	compare_error("a=b\rc", ParseErrorKind::MissingEquals, 4, 2, 1);
+}
+
+#[test]
+fn empty_key() {
+	compare_error("\u{feff} =b", ParseErrorKind::EmptyKey, 4, 1, 3);
+}
+
+#[test]
+fn error_message() {
+	let error = parse("a=b\nc").unwrap_err();
+	assert_eq!(error.message, "expected `=` after key");
+	assert_eq!(
+		error.to_string(),
+		"expected `=` after key at line 2, column 1"
+	);
+}
+
+#[test]
+fn all_entry_points_report_errors() {
+	assert!(parse("[a").is_err());
+	assert!(parse_to_vec("[a").is_err());
+	assert!(one_ini::parse_document("[a").is_err());
+}
+
+fn compare_error(contents: &str, kind: ParseErrorKind, offset: usize, line: usize, column: usize) {
+	let error = parse(contents).unwrap_err();
+	assert_eq!(error.kind, kind);
+	assert_eq!(
+		(error.offset, error.line, error.column),
+		(offset, line, column)
+	);
 }
 
 // Tests for the test harness
@@ -339,6 +420,7 @@ fn compare_vec_wrong_value() {
 	);
 }
 
+#[allow(clippy::ptr_arg)]
 fn compare_vec<S: Into<String>>(contents: S, expected: &Vec<(TokenTypes, &str)>) {
 	let s: String = contents.into();
 	let v = parse_to_vec(&s).unwrap();
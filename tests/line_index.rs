@@ -0,0 +1,111 @@
+//! Integration test suite for converting offsets into lines and columns.
+
+use one_ini::{parse_recovering, parse_to_vec, LineIndex, OffsetEncoding};
+
+#[test]
+fn every_line_break() {
+	let contents = "a\nb\r\nc\rd";
+	let index = LineIndex::new(contents);
+	assert_eq!(index.line_count(), 4);
+	let lines: Vec<&str> = (0..index.line_count())
+		.map(|line| &contents[index.line(line).unwrap()])
+		.collect();
+	assert_eq!(lines, ["a", "b", "c", "d"]);
+	assert_eq!(index.line(4), None);
+	for (line, offset) in [(0, 0), (1, 2), (2, 5), (3, 7)] {
+		assert_eq!(index.line_col(offset, OffsetEncoding::Utf8), (line, 0));
+		assert_eq!(index.offset(line, 0, OffsetEncoding::Utf8), Some(offset));
+	}
+}
+
+#[test]
+fn final_line_break_starts_an_empty_line() {
+	let index = LineIndex::new("a\r\n");
+	assert_eq!(index.line_count(), 2);
+	assert_eq!(index.line(1), Some(3..3));
+	assert_eq!(index.line_col(3, OffsetEncoding::Utf8), (1, 0));
+	assert_eq!(LineIndex::new("").line(0), Some(0..0));
+}
+
+#[test]
+fn columns_in_each_encoding() {
+	let contents = "é😀=x\n";
+	let index = LineIndex::new(contents);
+	let offset = contents.find('=').unwrap();
+	for (encoding, column) in [
+		(OffsetEncoding::Utf8, 6),
+		(OffsetEncoding::Utf16, 3),
+		(OffsetEncoding::CodePoint, 2),
+	] {
+		assert_eq!(index.line_col(offset, encoding), (0, column));
+		assert_eq!(index.offset(0, column, encoding), Some(offset));
+	}
+}
+
+#[test]
+fn columns_are_clamped() {
+	let contents = "ab\r\n😀\n";
+	let index = LineIndex::new(contents);
+	assert_eq!(index.offset(0, 10, OffsetEncoding::Utf8), Some(2));
+	// Within the surrogate pair of the emoji, which moves to its end.
+	assert_eq!(index.offset(1, 1, OffsetEncoding::Utf16), Some(8));
+	assert_eq!(index.offset(3, 0, OffsetEncoding::Utf8), None);
+}
+
+#[test]
+fn offsets_are_clamped() {
+	let contents = "a\n😀";
+	let index = LineIndex::new(contents);
+	// Within the emoji, which moves to its start.
+	assert_eq!(index.line_col(4, OffsetEncoding::Utf16), (1, 0));
+	assert_eq!(index.line_col(100, OffsetEncoding::Utf8), (1, 4));
+	let position = index.position(100);
+	assert_eq!((position.offset, position.line, position.column), (6, 2, 2));
+}
+
+#[test]
+fn positions_match_ast_spans() {
+	let contents = "\u{feff}root = true\r[*.{md,txt}]\r\n  é = 😀\n;x\nbad line\n";
+	let (ast, errors) = parse_recovering(contents);
+	let index = LineIndex::new(contents);
+	let mut spans = vec![];
+	for item in &ast.body {
+		spans.extend(item.span());
+		if let one_ini::Item::Section(section) = item {
+			spans.extend(section.body.iter().filter_map(|item| item.span()));
+		}
+	}
+	assert_eq!(spans.len(), 5);
+	for span in spans {
+		assert_eq!(index.span(span.start.offset, span.end.offset), span);
+		let start = span.start;
+		assert_eq!(
+			index.offset(start.line - 1, start.column - 1, OffsetEncoding::CodePoint),
+			Some(start.offset)
+		);
+	}
+	let error = &errors[0];
+	assert_eq!(
+		index.line_col(error.offset, OffsetEncoding::CodePoint),
+		(error.line - 1, error.column - 1)
+	);
+}
+
+#[test]
+fn round_trips_token_offsets() {
+	let contents = "[*]\r\nkey = 😀 value\rother=\n# comment";
+	let index = LineIndex::new(contents);
+	let tokens = parse_to_vec(contents).unwrap();
+	for encoding in [
+		OffsetEncoding::Utf8,
+		OffsetEncoding::Utf16,
+		OffsetEncoding::CodePoint,
+	] {
+		for token in tokens.chunks(3) {
+			for &offset in &token[1..] {
+				let (line, column) = index.line_col(offset as usize, encoding);
+				assert_eq!(index.offset(line, column, encoding), Some(offset as usize));
+			}
+		}
+	}
+}
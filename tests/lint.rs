@@ -0,0 +1,115 @@
+//! Integration test suite for linting.
+
+use one_ini::{lint, parse, parse_recovering, DiagnosticCode, Severity};
+
+#[test]
+fn clean_file_has_no_diagnostics() {
+	let ast = parse(
+		"root = true\n\n[*]\nindent_style = space\nindent_size = 2\nend_of_line = LF\ncharset = utf-8\n\
+		 trim_trailing_whitespace = true\ninsert_final_newline = false\nmax_line_length = off\n\n\
+		 [*.go]\nindent_style = tab\nindent_size = unset\ntab_width = 8\n",
+	)
+	.unwrap();
+	assert!(lint(&ast).is_empty());
+}
+
+#[test]
+fn commented_out_pairs_are_comments() {
+	let ast = parse("[*]\n# indent_size = 4\n; charset = latin1\n").unwrap();
+	assert!(lint(&ast).is_empty());
+}
+
+#[test]
+fn duplicate_keys() {
+	let ast = parse("[*]\nindent_size = 2\nINDENT_SIZE = 4\n[*.md]\nindent_size = 4\n").unwrap();
+	let diagnostics = lint(&ast);
+	assert_eq!(codes(&diagnostics), [DiagnosticCode::DuplicateKey]);
+	assert_eq!(diagnostics[0].severity, Severity::Warning);
+	assert_eq!(diagnostics[0].span.unwrap().start.line, 3);
+}
+
+#[test]
+fn duplicate_sections() {
+	let ast = parse("[*]\n[*.md]\n[*]\n[*.MD]\n").unwrap();
+	let diagnostics = lint(&ast);
+	assert_eq!(codes(&diagnostics), [DiagnosticCode::DuplicateSection]);
+	assert_eq!(diagnostics[0].message, "section `[*]` is already declared");
+	assert_eq!(diagnostics[0].span.unwrap().start.line, 3);
+}
+
+#[test]
+fn misplaced_root() {
+	let ast = parse("[*]\nroot = true\n").unwrap();
+	assert_eq!(codes(&lint(&ast)), [DiagnosticCode::MisplacedRoot]);
+}
+
+#[test]
+fn prelude_pairs_other_than_root() {
+	let ast = parse("root = maybe\nindent_style = tab\n").unwrap();
+	let diagnostics = lint(&ast);
+	assert_eq!(
+		codes(&diagnostics),
+		[DiagnosticCode::InvalidValue, DiagnosticCode::PreludePair]
+	);
+	assert_eq!(
+		diagnostics[0].message,
+		"`maybe` is not a valid `root`, expected `true` or `false`"
+	);
+}
+
+#[test]
+fn unknown_properties() {
+	let ast = parse("[*]\nquote_type = single\n").unwrap();
+	let diagnostics = lint(&ast);
+	assert_eq!(codes(&diagnostics), [DiagnosticCode::UnknownProperty]);
+	assert_eq!(diagnostics[0].severity, Severity::Info);
+}
+
+#[test]
+fn invalid_values() {
+	let ast = parse(
+		"[*]\nindent_style = tabz\nindent_size = 0\ntab_width = tab\nend_of_line = lfcr\n\
+		 charset = utf-32\ninsert_final_newline = yes\nmax_line_length = -1\n",
+	)
+	.unwrap();
+	let diagnostics = lint(&ast);
+	assert!(diagnostics
+		.iter()
+		.all(|d| d.code == DiagnosticCode::InvalidValue));
+	let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+	assert_eq!(
+		messages,
+		[
+			"`tabz` is not a valid `indent_style`, expected `tab` or `space`",
+			"`0` is not a valid `indent_size`, expected `tab` or a positive integer",
+			"`tab` is not a valid `tab_width`, expected a positive integer",
+			"`lfcr` is not a valid `end_of_line`, expected `lf`, `cr` or `crlf`",
+			"`utf-32` is not a valid `charset`, expected `latin1`, `utf-8`, `utf-8-bom`, `utf-16be` or `utf-16le`",
+			"`yes` is not a valid `insert_final_newline`, expected `true` or `false`",
+			"`-1` is not a valid `max_line_length`, expected a positive integer or `off`",
+		]
+	);
+	assert_eq!(diagnostics[0].span.unwrap().start.column, 16);
+}
+
+#[test]
+fn invalid_lines_from_recovering_parse() {
+	let (ast, _) = parse_recovering("[*]\nindent_style\n");
+	let diagnostics = lint(&ast);
+	assert_eq!(codes(&diagnostics), [DiagnosticCode::InvalidLine]);
+	assert_eq!(
+		diagnostics[0].to_string(),
+		"error[invalid-line]: `indent_style` is not a valid line, expected `=` after key at line 2, column 1"
+	);
+}
+
+#[test]
+fn codes_serialize_as_stable_names() {
+	let ast = parse("[*]\nroot=true\n").unwrap();
+	let json = serde_json::to_string(&lint(&ast)[0]).unwrap();
+	assert!(json.starts_with("{\"severity\":\"error\",\"code\":\"misplaced-root\""));
+}
+
+fn codes(diagnostics: &[one_ini::Diagnostic]) -> Vec<DiagnosticCode> {
+	diagnostics.iter().map(|d| d.code).collect()
+}
@@ -0,0 +1,71 @@
+//! Integration test suite for looking up and resolving config files.
+
+use one_ini::{find_configs, resolve_file, Item};
+use std::path::{Path, PathBuf};
+
+fn fixture(path: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/fixtures/lookup")
+		.join(path)
+}
+
+#[test]
+fn finds_configs_from_farthest_to_nearest() {
+	let configs = find_configs(fixture("nested/lib.rs"), ".editorconfig").unwrap();
+	let dirs: Vec<&Path> = configs.iter().map(|(dir, _)| dir.as_path()).collect();
+	assert_eq!(dirs, vec![fixture(""), fixture("nested")]);
+}
+
+#[test]
+fn stops_at_root() {
+	let configs = find_configs(fixture("nested/root/lib.rs"), ".editorconfig").unwrap();
+	assert_eq!(configs.len(), 1);
+	assert_eq!(configs[0].0, fixture("nested/root"));
+	assert!(configs[0].1.is_root());
+}
+
+#[test]
+fn skips_directories_without_config() {
+	let configs = find_configs(fixture("nested/missing/deeper/lib.rs"), ".editorconfig").unwrap();
+	assert_eq!(configs.len(), 2);
+}
+
+#[test]
+fn uses_custom_file_name() {
+	let configs = find_configs(fixture("nested/lib.rs"), ".missing").unwrap();
+	assert!(configs.iter().all(|(dir, _)| !dir.starts_with(fixture(""))));
+}
+
+#[test]
+fn resolves_file() {
+	let properties = resolve_file(fixture("nested/lib.rs")).unwrap();
+	assert_eq!(
+		properties.to_string(),
+		"indent_style=space\nindent_size=4\ntab_width=4\n"
+	);
+	let properties = resolve_file(fixture("nested/README.md")).unwrap();
+	assert_eq!(
+		properties.to_string(),
+		"indent_style=space\nindent_size=2\ntab_width=2\n"
+	);
+	let properties = resolve_file(fixture("nested/root/lib.rs")).unwrap();
+	assert_eq!(properties.to_string(), "end_of_line=lf\n");
+}
+
+#[test]
+fn skips_malformed_lines() {
+	let configs = find_configs(fixture("nested/malformed/lib.rs"), ".editorconfig").unwrap();
+	assert_eq!(configs.len(), 1);
+	let sections = configs[0].1.body.iter().filter_map(|item| match item {
+		Item::Section(section) => Some(section),
+		_ => None,
+	});
+	assert!(sections
+		.flat_map(|section| &section.body)
+		.all(|item| !matches!(item, Item::Error(_))));
+	let properties = resolve_file(fixture("nested/malformed/lib.rs")).unwrap();
+	assert_eq!(
+		properties.to_string(),
+		"indent_style=tab\nend_of_line=lf\nindent_size=tab\n"
+	);
+}
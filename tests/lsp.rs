@@ -0,0 +1,308 @@
+//! Integration test suite for the `one-ini-lsp` language server.
+
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const URI: &str = "file:///project/.editorconfig";
+
+#[test]
+fn initialize_advertises_capabilities() {
+	let responses = session(&[]);
+	let capabilities = &response(&responses, 1)["result"]["capabilities"];
+	assert_eq!(capabilities["textDocumentSync"], 1);
+	assert_eq!(capabilities["hoverProvider"], true);
+	assert_eq!(
+		capabilities["semanticTokensProvider"]["legend"]["tokenTypes"],
+		json!(["property", "string", "namespace", "comment", "comment"])
+	);
+}
+
+#[test]
+fn publishes_diagnostics() {
+	let responses = session(&[open("root=true\n[*]\nindent_style=tabs\n[*\n")]);
+	let diagnostics = &notification(&responses, "textDocument/publishDiagnostics")["params"];
+	assert_eq!(diagnostics["uri"], URI);
+	assert_eq!(
+		diagnostics["diagnostics"],
+		json!([
+			{
+				"range": {
+					"start": { "line": 2, "character": 13 },
+					"end": { "line": 2, "character": 17 },
+				},
+				"severity": 1,
+				"code": "invalid-value",
+				"source": "one-ini",
+				"message": "`tabs` is not a valid `indent_style`, expected `tab` or `space`",
+			},
+			{
+				"range": {
+					"start": { "line": 3, "character": 0 },
+					"end": { "line": 3, "character": 2 },
+				},
+				"severity": 1,
+				"code": "invalid-line",
+				"source": "one-ini",
+				"message": "`[*` is not a valid line, section header must end with `]`",
+			},
+		])
+	);
+}
+
+#[test]
+fn clears_diagnostics_on_close() {
+	let responses = session(&[
+		open("[*\n"),
+		json!({
+			"jsonrpc": "2.0",
+			"method": "textDocument/didClose",
+			"params": { "textDocument": { "uri": URI } },
+		}),
+	]);
+	let published: Vec<&Value> = responses
+		.iter()
+		.filter(|message| message["method"] == "textDocument/publishDiagnostics")
+		.collect();
+	assert_eq!(published.len(), 2);
+	assert_eq!(published[1]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn completes_property_names_and_values() {
+	let responses = session(&[
+		open("root=true\n[*]\nind\nend_of_line = c\n"),
+		request(2, "textDocument/completion", position(2, 3)),
+		request(3, "textDocument/completion", position(3, 15)),
+		request(4, "textDocument/completion", position(0, 2)),
+	]);
+	assert_eq!(labels(&response(&responses, 2)["result"]).len(), 9);
+	assert_eq!(
+		labels(&response(&responses, 3)["result"]),
+		["lf", "cr", "crlf", "unset"]
+	);
+	assert_eq!(labels(&response(&responses, 4)["result"]), ["root"]);
+}
+
+#[test]
+fn hovers_over_keys() {
+	let responses = session(&[
+		open("[*]\nIndent_Style = tab\n"),
+		request(2, "textDocument/hover", position(1, 3)),
+		request(3, "textDocument/hover", position(1, 16)),
+	]);
+	let hover = &response(&responses, 2)["result"];
+	assert!(hover["contents"]["value"]
+		.as_str()
+		.unwrap()
+		.starts_with("**indent_style**\n\nSet to `tab` or `space`"));
+	assert_eq!(hover["range"]["end"], json!({ "line": 1, "character": 12 }));
+	assert_eq!(response(&responses, 3)["result"], Value::Null);
+}
+
+#[test]
+fn lists_sections_as_symbols() {
+	let responses = session(&[
+		open("root=true\n[*.rs]\nindent_style=tab\n"),
+		request(2, "textDocument/documentSymbol", document()),
+	]);
+	let symbols = &response(&responses, 2)["result"];
+	assert_eq!(symbols[0]["name"], "root");
+	assert_eq!(symbols[1]["name"], "*.rs");
+	assert_eq!(symbols[1]["kind"], 3);
+	assert_eq!(
+		symbols[1]["selectionRange"],
+		json!({
+			"start": { "line": 1, "character": 1 },
+			"end": { "line": 1, "character": 5 },
+		})
+	);
+	assert_eq!(symbols[1]["children"][0]["name"], "indent_style");
+	assert_eq!(symbols[1]["children"][0]["detail"], "tab");
+}
+
+#[test]
+fn formats_with_initialization_options() {
+	let responses = session_with(
+		json!({ "format": { "spacesAroundEquals": true } }),
+		&[
+			open("[*]\r\nindent_style=tab\r\n"),
+			request(2, "textDocument/formatting", document()),
+		],
+	);
+	assert_eq!(
+		response(&responses, 2)["result"],
+		json!([{
+			"range": {
+				"start": { "line": 0, "character": 0 },
+				"end": { "line": 2, "character": 0 },
+			},
+			"newText": "[*]\nindent_style = tab\n",
+		}])
+	);
+}
+
+#[test]
+fn formatting_formatted_contents_changes_nothing() {
+	let responses = session(&[
+		open("[*]\nindent_style=tab\n"),
+		request(2, "textDocument/formatting", document()),
+	]);
+	assert_eq!(response(&responses, 2)["result"], json!([]));
+}
+
+#[test]
+fn encodes_semantic_tokens() {
+	let responses = session(&[
+		open("a=b\n[é]\n#c\n"),
+		request(2, "textDocument/semanticTokens/full", document()),
+	]);
+	assert_eq!(
+		response(&responses, 2)["result"]["data"],
+		json!([
+			0, 0, 1, 0, 0, // a
+			0, 2, 1, 1, 0, // b
+			1, 1, 1, 2, 0, // é
+			1, 0, 1, 3, 0, // #
+			0, 1, 1, 4, 0, // c
+		])
+	);
+}
+
+#[test]
+fn rejects_unknown_methods() {
+	let responses = session(&[request(2, "workspace/unknown", json!({}))]);
+	assert_eq!(response(&responses, 2)["error"]["code"], -32601);
+}
+
+#[test]
+fn exit_fails_without_shutdown() {
+	let status = spawn(&[
+		request(1, "initialize", json!({})),
+		json!({ "jsonrpc": "2.0", "method": "exit" }),
+	])
+	.0;
+	assert_eq!(status.code(), Some(1));
+}
+
+#[test]
+fn answers_malformed_messages_with_parse_errors() {
+	// A message without a length, then one whose body is not JSON.
+	let mut input = b"Content-Type: x\r\n\r\nContent-Length: 6\r\n\r\n{oops}".to_vec();
+	for message in [
+		request(2, "shutdown", Value::Null),
+		json!({ "jsonrpc": "2.0", "method": "exit" }),
+	] {
+		let body = message.to_string();
+		write!(input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+	}
+	let (status, responses) = spawn_with_input(&input);
+	assert!(status.success());
+	for response in &responses[..2] {
+		assert_eq!(response["id"], Value::Null);
+		assert_eq!(response["error"]["code"], -32700);
+	}
+	assert_eq!(response(&responses, 2)["result"], Value::Null);
+}
+
+/// Runs a session of initialize, the given messages, shutdown and exit,
+/// returning every message the server sent.
+fn session(messages: &[Value]) -> Vec<Value> {
+	session_with(Value::Null, messages)
+}
+
+fn session_with(initialization_options: Value, messages: &[Value]) -> Vec<Value> {
+	let mut script = vec![request(
+		1,
+		"initialize",
+		json!({ "capabilities": {}, "initializationOptions": initialization_options }),
+	)];
+	script.extend_from_slice(messages);
+	script.push(request(99, "shutdown", Value::Null));
+	script.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+	let (status, responses) = spawn(&script);
+	assert!(status.success());
+	assert_eq!(response(&responses, 99)["result"], Value::Null);
+	responses
+}
+
+fn spawn(messages: &[Value]) -> (std::process::ExitStatus, Vec<Value>) {
+	let mut input = vec![];
+	for message in messages {
+		let body = message.to_string();
+		write!(input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+	}
+	spawn_with_input(&input)
+}
+
+fn spawn_with_input(input: &[u8]) -> (std::process::ExitStatus, Vec<Value>) {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_one-ini-lsp"))
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.unwrap();
+	let mut stdin = child.stdin.take().unwrap();
+	stdin.write_all(input).unwrap();
+	drop(stdin);
+	let output = child.wait_with_output().unwrap();
+	(output.status, read_messages(&output.stdout))
+}
+
+fn read_messages(mut output: &[u8]) -> Vec<Value> {
+	let mut messages = vec![];
+	while !output.is_empty() {
+		let header_end = output.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+		let header = std::str::from_utf8(&output[..header_end]).unwrap();
+		let length: usize = header
+			.strip_prefix("Content-Length: ")
+			.unwrap()
+			.parse()
+			.unwrap();
+		let body = &output[header_end + 4..header_end + 4 + length];
+		messages.push(serde_json::from_slice(body).unwrap());
+		output = &output[header_end + 4 + length..];
+	}
+	messages
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+	json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn open(text: &str) -> Value {
+	json!({
+		"jsonrpc": "2.0",
+		"method": "textDocument/didOpen",
+		"params": {
+			"textDocument": { "uri": URI, "languageId": "editorconfig", "version": 1, "text": text },
+		},
+	})
+}
+
+fn document() -> Value {
+	json!({ "textDocument": { "uri": URI } })
+}
+
+fn position(line: u64, character: u64) -> Value {
+	json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+}
+
+fn response(messages: &[Value], id: u64) -> &Value {
+	messages.iter().find(|message| message["id"] == id).unwrap()
+}
+
+fn notification<'a>(messages: &'a [Value], method: &str) -> &'a Value {
+	messages
+		.iter()
+		.find(|message| message["method"] == method)
+		.unwrap()
+}
+
+fn labels(items: &Value) -> Vec<&str> {
+	items
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|item| item["label"].as_str().unwrap())
+		.collect()
+}
@@ -6,7 +6,12 @@
 extern crate wasm_bindgen_test;
 use std::assert_eq;
 
-use one_ini::parse_to_uint32array;
+use js_sys::Reflect;
+use one_ini::{
+	parse_string_to_uint32array, parse_string_to_uint32array_with_trivia, parse_to_json,
+	parse_to_uint32array, parse_to_uint32array_with_trivia,
+};
+use wasm_bindgen::JsValue;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen_test]
@@ -17,3 +22,73 @@ fn uint32array() {
 		Err(_) => assert!(false),
 	}
 }
+
+#[wasm_bindgen_test]
+fn uint32array_with_trivia() {
+	let expected: Vec<u32> = vec![5, 0, 1, 2, 1, 2, 6, 2, 3, 9, 3, 4, 11, 4, 6];
+	assert_eq!(
+		parse_to_uint32array_with_trivia(String::from("[a]\n[b").as_bytes()).unwrap(),
+		expected
+	);
+}
+
+#[wasm_bindgen_test]
+fn string_to_uint32array_in_utf16() {
+	let expected: Vec<u32> = vec![2, 1, 3, 0, 5, 6, 1, 7, 8];
+	assert_eq!(
+		parse_string_to_uint32array("[😀]\na=b", "utf16").unwrap(),
+		expected
+	);
+	assert!(parse_string_to_uint32array("a=b", "latin1").is_err());
+}
+
+#[wasm_bindgen_test]
+fn string_to_uint32array_with_trivia_in_utf16() {
+	let expected: Vec<u32> = vec![5, 0, 1, 2, 1, 3, 6, 3, 4, 9, 4, 5, 11, 5, 7];
+	assert_eq!(
+		parse_string_to_uint32array_with_trivia("[😀]\n[b", "utf16").unwrap(),
+		expected
+	);
+	assert!(parse_string_to_uint32array_with_trivia("a=b", "latin1").is_err());
+}
+
+#[wasm_bindgen_test]
+fn parse_to_json_throws_parse_error() {
+	let error = JsValue::from(parse_to_json("root=true\n  [*.md\n").unwrap_err());
+	let get = |key: &str| Reflect::get(&error, &JsValue::from_str(key)).unwrap();
+	assert_eq!(get("name"), "ParseError");
+	assert_eq!(get("kind"), "UnterminatedSectionHeader");
+	assert_eq!(get("line"), 2);
+	assert_eq!(get("column"), 3);
+	assert_eq!(get("offset"), 12);
+	assert_eq!(
+		get("message"),
+		"section header must end with `]` at line 2, column 3"
+	);
+}
+
+#[wasm_bindgen_test]
+fn format_with_options() {
+	let options = js_sys::Object::new();
+	Reflect::set(&options, &"spacesAroundEquals".into(), &true.into()).unwrap();
+	Reflect::set(&options, &"endOfLine".into(), &"crlf".into()).unwrap();
+	assert_eq!(
+		one_ini::format_with_options("[*]\na=b\n", options.into()).unwrap(),
+		"[*]\r\na = b\r\n"
+	);
+	assert_eq!(
+		one_ini::format_with_options("a = b\n", JsValue::UNDEFINED).unwrap(),
+		"a=b\n"
+	);
+}
+
+#[wasm_bindgen_test]
+fn fix_with_properties() {
+	let properties = js_sys::Object::new();
+	Reflect::set(&properties, &"end_of_line".into(), &"crlf".into()).unwrap();
+	Reflect::set(&properties, &"insert_final_newline".into(), &"true".into()).unwrap();
+	assert_eq!(
+		one_ini::fix_with_properties(b"a\nb", properties.into()).unwrap(),
+		b"a\r\nb\r\n"
+	);
+}
@@ -0,0 +1,160 @@
+//! Integration test suite for typed properties.
+
+use one_ini::{
+	parse, resolve, Charset, EndOfLine, IndentSize, IndentStyle, Item, MaxLineLength, Properties,
+	Property, PropertyError, Setting,
+};
+
+#[test]
+fn keys_and_values_are_case_insensitive() {
+	assert_eq!(
+		Property::new("INDENT_STYLE", "Space"),
+		Ok(Property::IndentStyle(Setting::Value(IndentStyle::Space)))
+	);
+	assert_eq!(
+		Property::new("charset", "UTF-8-BOM"),
+		Ok(Property::Charset(Setting::Value(Charset::Utf8Bom)))
+	);
+	assert_eq!(
+		Property::new("Trim_Trailing_Whitespace", "TRUE"),
+		Ok(Property::TrimTrailingWhitespace(Setting::Value(true)))
+	);
+	assert_eq!(Property::new("root", "True"), Ok(Property::Root(true)));
+}
+
+#[test]
+fn every_property_can_be_unset() {
+	for key in [
+		"indent_style",
+		"indent_size",
+		"tab_width",
+		"end_of_line",
+		"charset",
+		"trim_trailing_whitespace",
+		"insert_final_newline",
+		"max_line_length",
+		"spelling_language",
+	] {
+		let property = Property::new(key, "UNSET").unwrap();
+		assert_eq!(property.key(), key);
+	}
+	assert_eq!(
+		Property::new("max_line_length", "unset"),
+		Ok(Property::MaxLineLength(Setting::Unset))
+	);
+	assert!(Property::new("root", "unset").is_err());
+}
+
+#[test]
+fn numbers_must_be_positive_integers() {
+	assert_eq!(
+		Property::new("tab_width", "8"),
+		Ok(Property::TabWidth(Setting::Value(8)))
+	);
+	assert_eq!(
+		Property::new("max_line_length", "off"),
+		Ok(Property::MaxLineLength(Setting::Value(MaxLineLength::Off)))
+	);
+	for value in ["0", "-1", "+2", "2.5", "", "99999999999"] {
+		assert_eq!(
+			Property::new("tab_width", value),
+			Err(PropertyError::InvalidValue {
+				expected: "a positive integer"
+			}),
+			"{:?}",
+			value
+		);
+	}
+}
+
+#[test]
+fn unknown_properties() {
+	assert_eq!(
+		Property::new("quote_type", "single"),
+		Err(PropertyError::UnknownProperty)
+	);
+}
+
+#[test]
+fn values_display_in_canonical_form() {
+	assert_eq!(EndOfLine::Crlf.to_string(), "crlf");
+	assert_eq!(Charset::Utf16Le.to_string(), "utf-16le");
+	assert_eq!(IndentSize::Columns(4).to_string(), "4");
+	assert_eq!(IndentSize::Tab.to_string(), "tab");
+	assert_eq!(MaxLineLength::Off.to_string(), "off");
+}
+
+#[test]
+fn pairs_from_parsed_files() {
+	let ast = parse("[*]\nend_of_line = CRLF\n").unwrap();
+	let Item::Section(section) = &ast.body[0] else {
+		panic!("expected a section");
+	};
+	let Item::Pair(pair) = &section.body[0] else {
+		panic!("expected a pair");
+	};
+	assert_eq!(
+		pair.property(),
+		Ok(Property::EndOfLine(Setting::Value(EndOfLine::Crlf)))
+	);
+}
+
+#[test]
+fn resolved_properties() {
+	let ast = parse(
+		"root=true\n[*]\nindent_style=space\nindent_size=2\nend_of_line=lf\ninsert_final_newline=true\n\
+		 [*.md]\ntrim_trailing_whitespace=false\nmax_line_length=unset\n",
+	)
+	.unwrap();
+	let properties = resolve("README.md", &[("", &ast)]);
+	assert_eq!(properties.indent_style(), Some(IndentStyle::Space));
+	assert_eq!(properties.indent_size(), Some(IndentSize::Columns(2)));
+	assert_eq!(properties.tab_width(), Some(2));
+	assert_eq!(properties.end_of_line(), Some(EndOfLine::Lf));
+	assert_eq!(properties.charset(), None);
+	assert_eq!(properties.trim_trailing_whitespace(), Some(false));
+	assert_eq!(properties.insert_final_newline(), Some(true));
+	assert_eq!(properties.max_line_length(), None);
+}
+
+#[test]
+fn tab_width_defaults_to_indent_size() {
+	let properties = Properties::from_iter([("indent_size", "3")]);
+	assert_eq!(properties.tab_width(), Some(3));
+	let properties = Properties::from_iter([("indent_size", "3"), ("tab_width", "8")]);
+	assert_eq!(properties.tab_width(), Some(8));
+}
+
+#[test]
+fn indent_size_defaults_to_tab_for_tab_style() {
+	let properties = Properties::from_iter([("indent_style", "tab")]);
+	assert_eq!(properties.indent_size(), Some(IndentSize::Tab));
+	assert_eq!(properties.tab_width(), None);
+	let properties = Properties::from_iter([("indent_style", "tab"), ("indent_size", "unset")]);
+	assert_eq!(properties.indent_size(), None);
+}
+
+#[test]
+fn indent_size_tab_uses_tab_width() {
+	let properties = Properties::from_iter([("indent_size", "tab"), ("tab_width", "4")]);
+	assert_eq!(properties.indent_size(), Some(IndentSize::Columns(4)));
+	assert_eq!(properties.tab_width(), Some(4));
+}
+
+#[test]
+fn invalid_values_are_none() {
+	let properties = Properties::from_iter([("indent_style", "tabz"), ("indent_size", "big")]);
+	assert_eq!(properties.indent_style(), None);
+	assert_eq!(properties.indent_size(), None);
+	assert_eq!(properties.tab_width(), None);
+}
+
+#[test]
+fn keys_and_keywords_are_accepted() {
+	for key in Property::KEYS {
+		for keyword in Property::keywords(key) {
+			let property = Property::new(key, keyword).unwrap();
+			assert_eq!(property.key(), key);
+		}
+	}
+}
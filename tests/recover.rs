@@ -0,0 +1,85 @@
+//! Integration test suite for error-recovering parsing.
+
+use one_ini::{parse_recovering, Item, ParseErrorKind};
+
+#[test]
+fn valid_contents_have_no_errors() {
+	let contents = "root=true\n\n[*]\n# comment\nindent_style=tab\n";
+	let (ast, errors) = parse_recovering(contents);
+	assert!(errors.is_empty());
+	assert_eq!(
+		ast.to_string(),
+		one_ini::parse(contents).unwrap().to_string()
+	);
+}
+
+#[test]
+fn reports_every_invalid_line() {
+	let (_, errors) = parse_recovering("=a\nroot=true\n[*\n[*]\nfoo\n[a=b\n");
+	let found: Vec<(ParseErrorKind, usize, usize)> = errors
+		.iter()
+		.map(|error| (error.kind, error.line, error.column))
+		.collect();
+	assert_eq!(
+		found,
+		[
+			(ParseErrorKind::EmptyKey, 1, 1),
+			(ParseErrorKind::UnterminatedSectionHeader, 3, 1),
+			(ParseErrorKind::MissingEquals, 5, 1),
+			(ParseErrorKind::KeyStartsWithBracket, 6, 1),
+		]
+	);
+}
+
+#[test]
+fn keeps_error_nodes_in_place() {
+	let (ast, _) = parse_recovering("a=1\n  bad line  \nb=2\n[*]\nc=3\n[oops\nd=4\n");
+	assert!(matches!(&ast.body[0], Item::Pair(pair) if pair.key == "a"));
+	assert!(matches!(&ast.body[1], Item::Error(invalid) if invalid.text == "bad line"));
+	assert!(matches!(&ast.body[2], Item::Pair(pair) if pair.key == "b"));
+	let Item::Section(section) = &ast.body[3] else {
+		panic!("expected a section");
+	};
+	assert_eq!(section.body.len(), 3);
+	assert!(matches!(&section.body[0], Item::Pair(pair) if pair.key == "c"));
+	assert!(matches!(&section.body[1], Item::Error(invalid) if invalid.text == "[oops"));
+	assert!(matches!(&section.body[2], Item::Pair(pair) if pair.key == "d"));
+}
+
+#[test]
+fn error_nodes_have_spans() {
+	let (ast, errors) = parse_recovering("[*]\r\n\tnope \r\n");
+	let Item::Section(section) = &ast.body[0] else {
+		panic!("expected a section");
+	};
+	let span = section.body[0].span().unwrap();
+	assert_eq!((span.start.offset, span.end.offset), (6, 10));
+	assert_eq!((span.start.line, span.start.column), (2, 2));
+	assert_eq!(errors[0].offset, 6);
+	assert_eq!(section.span.unwrap().end.offset, 10);
+}
+
+#[test]
+fn trailing_invalid_line_extends_section() {
+	let (ast, _) = parse_recovering("[*]\na=b\nc");
+	let Item::Section(section) = &ast.body[0] else {
+		panic!("expected a section");
+	};
+	assert_eq!(section.span.unwrap().end.offset, 9);
+	assert_eq!(ast.to_string(), "[*]\na=b\nc\n");
+}
+
+#[test]
+fn stray_byte_order_mark_is_invalid() {
+	let (ast, errors) = parse_recovering("\u{feff}a=b\n\u{feff}\n");
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].line, 2);
+	assert!(matches!(&ast.body[1], Item::Error(invalid) if invalid.text == "\u{feff}"));
+}
+
+#[test]
+fn error_nodes_serialize_with_type() {
+	let (ast, _) = parse_recovering("oops\n");
+	let json = serde_json::to_string(&ast.body[0]).unwrap();
+	assert!(json.starts_with("{\"type\":\"Error\",\"text\":\"oops\",\"kind\":\"MissingEquals\""));
+}
@@ -0,0 +1,116 @@
+//! Integration test suite for the resolve function.
+
+use one_ini::{parse, resolve, resolve_for_version, OneINIAST, Version};
+
+#[test]
+fn matches_sections_by_extension() {
+	let ast = parse("[*.rs]\nindent_size=4\n[*.md]\nindent_size=2\n").unwrap();
+	assert_resolves("src/lib.rs", &[("", &ast)], "indent_size=4\ntab_width=4\n");
+	assert_resolves("README.md", &[("", &ast)], "indent_size=2\ntab_width=2\n");
+}
+
+#[test]
+fn later_sections_override_earlier_ones() {
+	let ast = parse("[*]\nend_of_line=lf\ncharset=utf-8\n[*.bat]\nend_of_line=crlf\n").unwrap();
+	assert_resolves(
+		"run.bat",
+		&[("", &ast)],
+		"end_of_line=crlf\ncharset=utf-8\n",
+	);
+}
+
+#[test]
+fn nearer_files_override_farther_ones() {
+	let root = parse("root=true\n[*]\nindent_style=space\nindent_size=2\n").unwrap();
+	let nested = parse("[*]\nindent_style=tab\n").unwrap();
+	assert_resolves(
+		"a/b/c.txt",
+		&[("", &root), ("a", &nested)],
+		"indent_style=tab\nindent_size=2\ntab_width=2\n",
+	);
+	assert_resolves(
+		"d/c.txt",
+		&[("", &root), ("a", &nested)],
+		"indent_style=space\nindent_size=2\ntab_width=2\n",
+	);
+}
+
+#[test]
+fn ignores_prelude_pairs() {
+	let ast = parse("root=true\nkey=value\n").unwrap();
+	assert_resolves("a.txt", &[("", &ast)], "");
+}
+
+#[test]
+fn ignores_commented_out_pairs() {
+	let ast = parse("[*]\n# indent_size = 4\n;charset=latin1\nindent_style=tab\n").unwrap();
+	assert_resolves(
+		"a.txt",
+		&[("", &ast)],
+		"indent_style=tab\nindent_size=tab\n",
+	);
+}
+
+#[test]
+fn lowercases_keys_and_known_values() {
+	let ast = parse("[*]\nIndent_Style=TAB\nCustom=MiXeD\n").unwrap();
+	assert_resolves(
+		"a.txt",
+		&[("", &ast)],
+		"indent_style=tab\ncustom=MiXeD\nindent_size=tab\n",
+	);
+}
+
+#[test]
+fn indent_size_tab_uses_tab_width() {
+	let ast = parse("[*]\nindent_size=tab\ntab_width=8\n").unwrap();
+	assert_resolves("a.txt", &[("", &ast)], "indent_size=8\ntab_width=8\n");
+}
+
+#[test]
+fn section_with_slash_is_relative_to_config() {
+	let ast = parse("[src/*.rs]\na=1\n[/top.txt]\nb=2\n").unwrap();
+	assert_resolves("src/lib.rs", &[("", &ast)], "a=1\n");
+	assert_resolves("nested/src/lib.rs", &[("", &ast)], "");
+	assert_resolves("top.txt", &[("", &ast)], "b=2\n");
+	assert_resolves("nested/top.txt", &[("", &ast)], "");
+}
+
+#[test]
+fn section_without_slash_matches_at_any_depth() {
+	let ast = parse("[lib.rs]\na=1\n").unwrap();
+	assert_resolves("lib.rs", &[("", &ast)], "a=1\n");
+	assert_resolves("src/lib.rs", &[("", &ast)], "a=1\n");
+	assert_resolves("src/mylib.rs", &[("", &ast)], "");
+}
+
+#[test]
+fn skips_configs_outside_the_path() {
+	let ast = parse("[*]\na=1\n").unwrap();
+	assert_resolves("src/lib.rs", &[("tests", &ast)], "");
+}
+
+fn assert_resolves(path: &str, configs: &[(&str, &OneINIAST)], expected: &str) {
+	assert_eq!(resolve(path, configs).to_string(), expected);
+}
+
+#[test]
+fn versions_before_0_10_0_do_not_default_indent_size() {
+	let ast = parse("[*]\nindent_style=tab\ntab_width=4\n").unwrap();
+	let properties = resolve_for_version("a.txt", &[("", &ast)], Version::new(0, 9, 0));
+	assert_eq!(properties.to_string(), "indent_style=tab\ntab_width=4\n");
+	let properties = resolve_for_version("a.txt", &[("", &ast)], Version::new(0, 10, 0));
+	assert_eq!(
+		properties.to_string(),
+		"indent_style=tab\ntab_width=4\nindent_size=4\n"
+	);
+}
+
+#[test]
+fn parses_versions() {
+	assert_eq!("1".parse(), Ok(Version::new(1, 0, 0)));
+	assert_eq!("0.12.5".parse(), Ok(Version::new(0, 12, 5)));
+	for invalid in ["", "a", "0..1", "0.1.2.3", "-1", "0.1."] {
+		assert!(invalid.parse::<Version>().is_err(), "{:?}", invalid);
+	}
+}
@@ -0,0 +1,183 @@
+//! Integration test suite for serializing Rust types as INI contents with
+//! serde.
+
+use one_ini::{from_str, to_string};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Server {
+	host: String,
+	port: u16,
+	debug: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+	Fast,
+	SafeAndSlow,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Config {
+	name: String,
+	server: Server,
+	retries: i32,
+	ratio: f64,
+	mode: Mode,
+	ports: Vec<u16>,
+	comment: Option<String>,
+}
+
+fn config() -> Config {
+	Config {
+		name: String::from("api"),
+		server: Server {
+			host: String::from("localhost"),
+			port: 8080,
+			debug: true,
+		},
+		retries: -3,
+		ratio: 0.5,
+		mode: Mode::SafeAndSlow,
+		ports: vec![80, 443],
+		comment: None,
+	}
+}
+
+#[test]
+fn scalars_to_the_prelude_and_structs_to_sections() {
+	assert_eq!(
+		to_string(&config()).unwrap(),
+		"name=api\nretries=-3\nratio=0.5\nmode=safe_and_slow\nports=80, 443\n\n\
+		 [server]\nhost=localhost\nport=8080\ndebug=true\n"
+	);
+}
+
+#[test]
+fn round_trips_through_from_str() {
+	let config = config();
+	assert_eq!(
+		from_str::<Config>(&to_string(&config).unwrap()).unwrap(),
+		config
+	);
+}
+
+#[test]
+fn maps() {
+	let mut sections = BTreeMap::new();
+	sections.insert("*.md", HashMap::from([("indent_size", 2)]));
+	sections.insert("*.rs", HashMap::from([("indent_size", 4)]));
+	assert_eq!(
+		to_string(&sections).unwrap(),
+		"[*.md]\nindent_size=2\n\n[*.rs]\nindent_size=4\n"
+	);
+	let prelude = BTreeMap::from([(1, 'a'), (2, 'b')]);
+	assert_eq!(to_string(&prelude).unwrap(), "1=a\n2=b\n");
+}
+
+#[test]
+fn none_fields_are_omitted() {
+	#[derive(Serialize)]
+	struct Optional {
+		value: Option<u8>,
+		section: Option<BTreeMap<String, String>>,
+		inner: BTreeMap<&'static str, Option<u8>>,
+	}
+
+	let optional = Optional {
+		value: None,
+		section: None,
+		inner: BTreeMap::from([("a", None), ("b", Some(1))]),
+	};
+	assert_eq!(to_string(&optional).unwrap(), "[inner]\nb=1\n");
+}
+
+#[test]
+fn empty_values() {
+	#[derive(Serialize)]
+	struct Empty {
+		unit: (),
+		text: &'static str,
+		list: Vec<u8>,
+	}
+
+	let empty = Empty {
+		unit: (),
+		text: "",
+		list: vec![],
+	};
+	assert_eq!(to_string(&empty).unwrap(), "unit=\ntext=\nlist=\n");
+}
+
+#[test]
+fn nested_sections_are_errors() {
+	let nested = BTreeMap::from([("a", BTreeMap::from([("b", BTreeMap::from([("c", 1)]))]))]);
+	assert_eq!(
+		to_string(&nested).unwrap_err().to_string(),
+		"sections cannot be nested in `a.b`"
+	);
+}
+
+#[test]
+fn line_breaks_are_errors() {
+	let value = BTreeMap::from([("a", "b\nc")]);
+	assert_eq!(
+		to_string(&value).unwrap_err().to_string(),
+		"value contains a line break in `a`"
+	);
+	let section = BTreeMap::from([("s", BTreeMap::from([("a", "b\r")]))]);
+	assert_eq!(
+		to_string(&section).unwrap_err().to_string(),
+		"value contains a line break in `s.a`"
+	);
+	let name = BTreeMap::from([("a\nb", BTreeMap::from([("c", 1)]))]);
+	assert!(to_string(&name)
+		.unwrap_err()
+		.to_string()
+		.starts_with("invalid section name"));
+}
+
+#[test]
+fn unrepresentable_keys_and_values_are_errors() {
+	for key in ["", "a=b", "[a", "#a", ";a", " a", "a\t"] {
+		let error = to_string(&BTreeMap::from([(key, 1)])).unwrap_err();
+		assert!(error.to_string().starts_with("invalid key"), "{:?}", key);
+	}
+	assert_eq!(
+		to_string(&BTreeMap::from([("a", " b")]))
+			.unwrap_err()
+			.to_string(),
+		"value starts or ends with whitespace, which would be trimmed in `a`"
+	);
+	assert_eq!(
+		to_string(&BTreeMap::from([("a", vec!["b,c"])]))
+			.unwrap_err()
+			.to_string(),
+		"list item `b,c` contains `,` or starts or ends with whitespace in `a`"
+	);
+}
+
+#[test]
+fn values_at_the_top_level_are_errors() {
+	assert_eq!(
+		to_string(&1).unwrap_err().to_string(),
+		"expected a struct or map, found a value"
+	);
+}
+
+#[test]
+fn enum_variants_with_data_are_errors() {
+	#[derive(Serialize)]
+	enum Shape {
+		Circle(u8),
+	}
+
+	assert_eq!(
+		to_string(&BTreeMap::from([("shape", Shape::Circle(1))]))
+			.unwrap_err()
+			.to_string(),
+		"enum variant `Shape::Circle` has data, which INI cannot represent in `shape`"
+	);
+}
@@ -0,0 +1,96 @@
+//! Integration test suite for the spans of parsed nodes.
+
+use one_ini::{parse, Item, Position, Span};
+
+#[test]
+fn pair_spans() {
+	let ast = parse("  key = value  \n").unwrap();
+	let Item::Pair(pair) = &ast.body[0] else {
+		panic!("expected a pair")
+	};
+	assert_eq!(pair.span, Some(span((2, 1, 3), (13, 1, 14))));
+	assert_eq!(pair.key_span, Some(span((2, 1, 3), (5, 1, 6))));
+	assert_eq!(pair.value_span, Some(span((8, 1, 9), (13, 1, 14))));
+}
+
+#[test]
+fn empty_value_span() {
+	let ast = parse("key=\n").unwrap();
+	let Item::Pair(pair) = &ast.body[0] else {
+		panic!("expected a pair")
+	};
+	assert_eq!(pair.value_span, Some(span((4, 1, 5), (4, 1, 5))));
+}
+
+#[test]
+fn comment_span() {
+	let ast = parse("a=b\n # c \n").unwrap();
+	assert_eq!(ast.body[1].span(), Some(span((5, 2, 2), (9, 2, 6))));
+}
+
+#[test]
+fn section_spans() {
+	let ast = parse("[a]\nb=c\n\n[d]  \n").unwrap();
+	let Item::Section(first) = &ast.body[0] else {
+		panic!("expected a section")
+	};
+	assert_eq!(first.span, Some(span((0, 1, 1), (7, 2, 4))));
+	assert_eq!(first.name_span, Some(span((1, 1, 2), (2, 1, 3))));
+	let Item::Section(second) = &ast.body[1] else {
+		panic!("expected a section")
+	};
+	assert_eq!(second.span, Some(span((9, 4, 1), (12, 4, 4))));
+	assert!(second.body.is_empty());
+}
+
+#[test]
+fn lines_end_at_any_newline() {
+	let ast = parse("a=b\r\nc=d\re=f\ng=h").unwrap();
+	let lines: Vec<usize> = ast
+		.body
+		.iter()
+		.map(|item| item.span().unwrap().start.line)
+		.collect();
+	assert_eq!(lines, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn columns_count_characters() {
+	let ast = parse("\u{feff}é=ü").unwrap();
+	let Item::Pair(pair) = &ast.body[0] else {
+		panic!("expected a pair")
+	};
+	assert_eq!(pair.value_span, Some(span((6, 1, 4), (8, 1, 5))));
+}
+
+#[test]
+fn serializes_spans() {
+	let ast = parse("a=b").unwrap();
+	let json = serde_json::to_string(&ast.body[0]).unwrap();
+	assert_eq!(
+		json,
+		concat!(
+			"{\"type\":\"Pair\",\"key\":\"a\",\"value\":\"b\",",
+			"\"span\":{\"start\":{\"offset\":0,\"line\":1,\"column\":1},\"end\":{\"offset\":3,\"line\":1,\"column\":4}},",
+			"\"key_span\":{\"start\":{\"offset\":0,\"line\":1,\"column\":1},\"end\":{\"offset\":1,\"line\":1,\"column\":2}},",
+			"\"value_span\":{\"start\":{\"offset\":2,\"line\":1,\"column\":3},\"end\":{\"offset\":3,\"line\":1,\"column\":4}}}"
+		)
+	);
+	let item: Item = serde_json::from_str(&json).unwrap();
+	assert_eq!(item.span(), ast.body[0].span());
+}
+
+fn span(start: (usize, usize, usize), end: (usize, usize, usize)) -> Span {
+	Span {
+		start: Position {
+			offset: start.0,
+			line: start.1,
+			column: start.2,
+		},
+		end: Position {
+			offset: end.0,
+			line: end.1,
+			column: end.2,
+		},
+	}
+}
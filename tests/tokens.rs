@@ -0,0 +1,265 @@
+//! Integration test suite for the token iterator and token streams with
+//! trivia.
+
+use one_ini::{parse_to_vec, parse_to_vec_with_trivia, tokenize, Token, TokenTypes};
+use std::fs;
+use std::path::Path;
+
+fn tokens(contents: &str) -> Vec<(TokenTypes, &str)> {
+	parse_to_vec_with_trivia(contents)
+		.chunks(3)
+		.map(|token| {
+			let kind = TokenTypes::try_from(token[0]).unwrap();
+			(kind, &contents[token[1] as usize..token[2] as usize])
+		})
+		.collect()
+}
+
+fn assert_covers(contents: &str) {
+	let mut offset = 0;
+	for token in parse_to_vec_with_trivia(contents).chunks(3) {
+		assert_eq!(token[1] as usize, offset, "{:?}", contents);
+		offset = token[2] as usize;
+	}
+	assert_eq!(offset, contents.len(), "{:?}", contents);
+}
+
+fn fixtures(dir: &Path, contents: &mut Vec<String>) {
+	for entry in fs::read_dir(dir).unwrap() {
+		let path = entry.unwrap().path();
+		match path.is_dir() {
+			true => fixtures(&path, contents),
+			false => contents.extend(fs::read_to_string(&path).ok()),
+		}
+	}
+}
+
+#[test]
+fn every_byte_is_covered() {
+	let mut inputs: Vec<String> = [
+		"",
+		"\n",
+		"a=b",
+		"  a  =  b  \n",
+		"[*]\r\na=b\r\n",
+		"[*]\ra=b\r",
+		"\u{feff}[*]\n",
+		"a=\n",
+		"[*\n a\n=b\n",
+		"#\n;comment\n\t# indented \n",
+		"[ é ]\nkey = välue ; not a comment\n",
+	]
+	.map(String::from)
+	.to_vec();
+	fixtures(Path::new("tests/fixtures"), &mut inputs);
+	for contents in &inputs {
+		assert_covers(contents);
+	}
+}
+
+#[test]
+fn significant_tokens_match_parse_to_vec() {
+	let contents = fs::read_to_string("tests/fixtures/config.ini").unwrap();
+	let trivia = [
+		TokenTypes::SectionOpen,
+		TokenTypes::SectionClose,
+		TokenTypes::Equals,
+		TokenTypes::Whitespace,
+		TokenTypes::Newline,
+		TokenTypes::Bom,
+	]
+	.map(|kind| kind as u32);
+	let significant: Vec<u32> = parse_to_vec_with_trivia(&contents)
+		.chunks(3)
+		.filter(|token| !trivia.contains(&token[0]))
+		.flatten()
+		.copied()
+		.collect();
+	assert_eq!(significant, parse_to_vec(&contents).unwrap());
+}
+
+#[test]
+fn sections_and_pairs() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("[*.md]\n\tkey = a value\n"),
+		[
+			(SectionOpen, "["),
+			(Section, "*.md"),
+			(SectionClose, "]"),
+			(Newline, "\n"),
+			(Whitespace, "\t"),
+			(Key, "key"),
+			(Whitespace, " "),
+			(Equals, "="),
+			(Whitespace, " "),
+			(Value, "a value"),
+			(Newline, "\n"),
+		]
+	);
+}
+
+#[test]
+fn empty_values() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("a=\n"),
+		[(Key, "a"), (Equals, "="), (Value, ""), (Newline, "\n")]
+	);
+}
+
+#[test]
+fn comments() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("# a comment\n;\n"),
+		[
+			(CommentIndicator, "#"),
+			(CommentValue, " a comment"),
+			(Newline, "\n"),
+			(CommentIndicator, ";"),
+			(CommentValue, ""),
+			(Newline, "\n"),
+		]
+	);
+}
+
+#[test]
+fn line_breaks() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("\r\n\n\r"),
+		[(Newline, "\r\n"), (Newline, "\n"), (Newline, "\r")]
+	);
+}
+
+#[test]
+fn byte_order_mark() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("\u{feff}a=b"),
+		[(Bom, "\u{feff}"), (Key, "a"), (Equals, "="), (Value, "b")]
+	);
+}
+
+#[test]
+fn invalid_lines() {
+	use TokenTypes::*;
+	assert_eq!(
+		tokens("[*\nkey\na=b\n"),
+		[
+			(Invalid, "[*"),
+			(Newline, "\n"),
+			(Invalid, "key"),
+			(Newline, "\n"),
+			(Key, "a"),
+			(Equals, "="),
+			(Value, "b"),
+			(Newline, "\n"),
+		]
+	);
+}
+
+#[test]
+fn token_types_from_u32() {
+	for kind in [
+		TokenTypes::SectionOpen,
+		TokenTypes::SectionClose,
+		TokenTypes::Equals,
+		TokenTypes::Whitespace,
+		TokenTypes::Newline,
+		TokenTypes::Bom,
+		TokenTypes::Invalid,
+	] {
+		assert_eq!(TokenTypes::try_from(kind as u32), Ok(kind));
+	}
+	assert_eq!(TokenTypes::try_from(12), Err(12));
+}
+
+#[test]
+fn tokenize_matches_parse_to_vec() {
+	let mut inputs = vec![
+		String::from("\u{feff}root = true\r\n[*]\r\n; a\r\nb =\r\n"),
+		String::from("a=b\r[c]\r"),
+		String::from("  [ spaced ]  \n  key  =  value  \n"),
+	];
+	fixtures(Path::new("tests/fixtures"), &mut inputs);
+	for contents in &inputs {
+		let Ok(expected) = parse_to_vec(contents) else {
+			continue;
+		};
+		let tokens: Vec<u32> = tokenize(contents)
+			.flat_map(|token| {
+				assert_eq!(token.text, &contents[token.span.clone()]);
+				[
+					token.kind as u32,
+					token.span.start as u32,
+					token.span.end as u32,
+				]
+			})
+			.collect();
+		assert_eq!(tokens, expected, "{:?}", contents);
+	}
+}
+
+#[test]
+fn tokenize_borrows_token_text() {
+	let contents = String::from("[*.md]\nkey = a value\n# comment\n");
+	let tokens: Vec<Token> = tokenize(&contents).collect();
+	assert_eq!(
+		tokens,
+		[
+			Token {
+				kind: TokenTypes::Section,
+				span: 1..5,
+				text: "*.md",
+			},
+			Token {
+				kind: TokenTypes::Key,
+				span: 7..10,
+				text: "key",
+			},
+			Token {
+				kind: TokenTypes::Value,
+				span: 13..20,
+				text: "a value",
+			},
+			Token {
+				kind: TokenTypes::CommentIndicator,
+				span: 21..22,
+				text: "#",
+			},
+			Token {
+				kind: TokenTypes::CommentValue,
+				span: 22..30,
+				text: " comment",
+			},
+		]
+	);
+}
+
+#[test]
+fn tokenize_goes_on_after_invalid_lines() {
+	use TokenTypes::*;
+	let tokens: Vec<(TokenTypes, &str)> = tokenize("[*\n\tno equals \r\na=b")
+		.map(|token| (token.kind, token.text))
+		.collect();
+	assert_eq!(
+		tokens,
+		[
+			(Invalid, "[*"),
+			(Invalid, "no equals"),
+			(Key, "a"),
+			(Value, "b")
+		]
+	);
+}
+
+#[test]
+fn tokenize_is_lazy() {
+	let contents = "a=b\n".repeat(100_000);
+	let mut tokens = tokenize(&contents);
+	assert_eq!(tokens.nth(2).unwrap().span, 4..5);
+	assert_eq!(tokenize("").next(), None);
+	assert_eq!(tokenize("\n\n  \n").next(), None);
+}